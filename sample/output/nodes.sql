@@ -1,3 +1,2 @@
-DROP TABLE IF EXISTS nodes;
-CREATE TABLE nodes (id INTEGER,lat DECIMAL(10,8),lng DECIMAL(10,8),version INTEGER,changeset INTEGER,user VARCHAR(150),uid INTEGER,visible TINYINT(2),date_time VARCHAR(150),CONSTRAINT nodes_pk PRIMARY KEY(id));
-INSERT INTO nodes (id,lat,lng, version, changeset, user, uid, visible, date_time) VALUES ("298884269","54.090176","12.248263","1","676636","SvenHRO","46882","1","2008-09-21T21:37:45Z") , ("261728686","54.09063","12.244192","1","323878","PikoWinter","36744","1","2008-05-03T13:39:23Z") , ("1831881213","54.090065","12.253938","1","12370172","lafkor","75625","1","2012-07-20T09:43:19Z") , ("298884272","54.090145","12.251652","1","676636","SvenHRO","46882","1","2008-09-21T21:37:45Z") 
\ No newline at end of file
+CREATE TABLE nodes (id BIGINT,lat DECIMAL(10,7),lng DECIMAL(10,7),version INTEGER,changeset INTEGER,user VARCHAR(256),uid INTEGER,visible TINYINT(2),timestamp VARCHAR(256),CONSTRAINT nodes_pk PRIMARY KEY(id)) DEFAULT CHARSET=utf8mb4;
+INSERT IGNORE INTO nodes (id,lat,lng,version,changeset,user,uid,visible,timestamp) VALUES (298884269,54.0901746,12.2482632,1,676636,'SvenHRO',46882,1,'2008-09-21T21:37:45Z'),(261728686,54.0906309,12.2441924,1,323878,'PikoWinter',36744,1,'2008-05-03T13:39:23Z'),(1831881213,54.0900666,12.2539381,1,12370172,'lafkor',75625,1,'2012-07-20T09:43:19Z'),(298884272,54.0901447,12.2516513,1,676636,'SvenHRO',46882,1,'2008-09-21T21:37:45Z');
@@ -1,4 +1,2 @@
-DROP TABLE IF EXISTS ref_tags;
-CREATE TABLE ref_tags (rt_id INTEGER AUTO_INCREMENT,
-                tag_id INTEGER,node_id INTEGER DEFAULT NULL,relation_id INTEGER DEFAULT NULL,way_id INTEGER DEFAULT NULL,value VARCHAR(250),CONSTRAINT ref_tags_pk PRIMARY KEY(rt_id),CONSTRAINT  ref_tags_tags_fk FOREIGN KEY(tag_id) REFERENCES tags(id),CONSTRAINT  ref_tags_nodes_fk FOREIGN KEY(node_id) REFERENCES nodes(id),CONSTRAINT  ref_tags_relations_fk FOREIGN KEY(relation_id) REFERENCES relations(id),CONSTRAINT  ref_tags_ways_fk FOREIGN KEY(way_id) REFERENCES ways(id));
-INSERT INTO ref_tags (tag_id,node_id,way_id,relation_id,value) VALUES ("0",1831881213,NULL,NULL,"Neu Broderstorf") , ("1",1831881213,NULL,NULL,"city_limit") , ("2",NULL,26659127,NULL,"unclassified") , ("0",NULL,26659127,NULL,"Pastower Straße") , ("0",NULL,NULL,56688,"Küstenbus Linie 123") , ("3",NULL,NULL,56688,"VVW") , ("4",NULL,NULL,56688,"Regionalverkehr Küste") , ("5",NULL,NULL,56688,"123") , ("6",NULL,NULL,56688,"bus") , ("7",NULL,NULL,56688,"route") 
\ No newline at end of file
+CREATE TABLE ref_tags (rt_id BIGINT AUTO_INCREMENT,tag_id INTEGER,node_id BIGINT,relation_id BIGINT,way_id BIGINT,value VARCHAR(256),CONSTRAINT ref_tags_pk PRIMARY KEY(rt_id),CONSTRAINT ref_tags_tags_fk FOREIGN KEY(tag_id) REFERENCES tags(id),CONSTRAINT ref_tags_nodes_fk FOREIGN KEY(node_id) REFERENCES nodes(id),CONSTRAINT ref_tags_relations_fk FOREIGN KEY(relation_id) REFERENCES relations(id),CONSTRAINT ref_tags_ways_fk FOREIGN KEY(way_id) REFERENCES ways(id)) DEFAULT CHARSET=utf8mb4;
+INSERT IGNORE INTO ref_tags (tag_id,node_id,relation_id,way_id,value) VALUES (0,1831881213,NULL,NULL,'Neu Broderstorf'),(1,1831881213,NULL,NULL,'city_limit'),(2,NULL,NULL,26659127,'unclassified'),(0,NULL,NULL,26659127,'Pastower Straße'),(0,NULL,56688,NULL,'Küstenbus Linie 123'),(3,NULL,56688,NULL,'VVW'),(4,NULL,56688,NULL,'Regionalverkehr Küste'),(5,NULL,56688,NULL,'123'),(6,NULL,56688,NULL,'bus'),(7,NULL,56688,NULL,'route');
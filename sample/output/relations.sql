@@ -1,3 +1,2 @@
-DROP TABLE IF EXISTS relations;
-CREATE TABLE relations (id INTEGER,version INTEGER,changeset INTEGER,user VARCHAR(150),uid INTEGER,visible TINYINT(2),date_time VARCHAR(150),CONSTRAINT relations_pk PRIMARY KEY(id));
-INSERT INTO relations (id, version, changeset, user, uid, visible, date_time) VALUES ("56688","28","6947637","kmvar","56190","1","2011-01-12T14:23:49Z") 
\ No newline at end of file
+CREATE TABLE relations (id BIGINT,version INTEGER,changeset INTEGER,user VARCHAR(256),uid INTEGER,visible TINYINT(2),timestamp VARCHAR(256),CONSTRAINT relations_pk PRIMARY KEY(id)) DEFAULT CHARSET=utf8mb4;
+INSERT IGNORE INTO relations (id,version,changeset,user,uid,visible,timestamp) VALUES (56688,28,6947637,'kmvar',56190,1,'2011-01-12T14:23:49Z');
@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::path::Path;
+use std::sync::mpsc::Sender;
+use std::thread::JoinHandle;
+
+use crate::copy_writer::write_load_script;
+use crate::geometry::{self, NodeLocations};
+use crate::handler::Handler;
+use crate::models::*;
+use crate::multipolygon::{self, Assembler};
+use crate::sqlite_writer::{close_database, open_database, Database};
+use crate::tags::TagStorage;
+use crate::writer::{is_stdout, new_thread, write_spooled, Format, SpoolFile, ThreadSignal};
+use crate::Arguments;
+
+/// Receives the parsed OSM elements from an input reader and sends the
+/// rows to the writer threads of each table.
+///
+/// The elements are passed with their tags, way nodes and relation members.
+/// Elements of the types that are not written are skipped.
+pub struct Converter {
+    nodes: Option<Sender<ThreadSignal<Node>>>,
+    tags: Option<Sender<ThreadSignal<Tag>>>,
+    ways: Option<Sender<ThreadSignal<Way>>>,
+    way_nodes: Option<Sender<ThreadSignal<WayNode>>>,
+    relations: Option<Sender<ThreadSignal<Relation>>>,
+    relation_members: Option<Sender<ThreadSignal<RelationMember>>>,
+    ref_tags: Option<Sender<ThreadSignal<UsedTag>>>,
+    tag_values: Option<Sender<ThreadSignal<TagValue>>>,
+    handles: Vec<JoinHandle<()>>,
+    /// Written tables in the import order.
+    tables: Vec<Table>,
+    /// Temporary files of the tables when writing to the standard output.
+    spool_files: Vec<SpoolFile>,
+    arguments: Arguments,
+    database: Option<Database>,
+    /// Ids of the tag keys in the order of their first appearance.
+    used_tags: HashMap<String, i32>,
+    /// Ids of the tag values when the values are normalized.
+    used_values: HashMap<String, i32>,
+    /// Locations of the nodes when the geometries are written.
+    locations: Option<NodeLocations>,
+    /// Assembler of the area relations when their geometries are written.
+    assembler: Option<Assembler>,
+}
+
+impl Converter {
+    pub fn new(arguments: &Arguments) -> Converter {
+        let assembler = if multipolygon::is_enabled(arguments) {
+            Some(Assembler::read(Path::new(&arguments.input)))
+        } else {
+            None
+        };
+
+        let database = if arguments.format == Format::Database {
+            Some(open_database(&arguments.output))
+        } else {
+            None
+        };
+
+        let mut handles = vec![];
+        let mut tables = vec![];
+
+        let nodes = if arguments.types.contains(&ElementType::Node) {
+            let (nodes_handle, nodes) = new_thread::<Node>(arguments.clone(), database.clone());
+            handles.push(nodes_handle);
+            tables.push(Node::get_table(arguments));
+            Some(nodes)
+        } else {
+            None
+        };
+
+        let (ways, way_nodes) = if arguments.types.contains(&ElementType::Way) {
+            let (ways_handle, ways) = new_thread::<Way>(arguments.clone(), database.clone());
+            let (way_nodes_handle, way_nodes) =
+                new_thread::<WayNode>(arguments.clone(), database.clone());
+            handles.extend([ways_handle, way_nodes_handle]);
+            tables.extend([Way::get_table(arguments), WayNode::get_table(arguments)]);
+            (Some(ways), Some(way_nodes))
+        } else {
+            (None, None)
+        };
+
+        let (relations, relation_members) = if arguments.types.contains(&ElementType::Relation) {
+            let (relations_handle, relations) =
+                new_thread::<Relation>(arguments.clone(), database.clone());
+            let (relation_members_handle, relation_members) =
+                new_thread::<RelationMember>(arguments.clone(), database.clone());
+            handles.extend([relations_handle, relation_members_handle]);
+            tables.extend([
+                Relation::get_table(arguments),
+                RelationMember::get_table(arguments),
+            ]);
+            (Some(relations), Some(relation_members))
+        } else {
+            (None, None)
+        };
+
+        let (tags, tag_values, ref_tags) = if arguments.tag_storage == TagStorage::Table {
+            let (tags_handle, tags) = new_thread::<Tag>(arguments.clone(), database.clone());
+            handles.push(tags_handle);
+            tables.push(Tag::get_table(arguments));
+
+            let tag_values = if arguments.normalize_values {
+                let (tag_values_handle, tag_values) =
+                    new_thread::<TagValue>(arguments.clone(), database.clone());
+                handles.push(tag_values_handle);
+                tables.push(TagValue::get_table(arguments));
+                Some(tag_values)
+            } else {
+                None
+            };
+
+            let (ref_tags_handle, ref_tags) =
+                new_thread::<UsedTag>(arguments.clone(), database.clone());
+            handles.push(ref_tags_handle);
+            tables.push(UsedTag::get_table(arguments));
+
+            (Some(tags), tag_values, Some(ref_tags))
+        } else {
+            (None, None, None)
+        };
+
+        let spool_files = if is_stdout(arguments) {
+            SpoolFile::for_tables(&tables)
+        } else {
+            vec![]
+        };
+
+        Converter {
+            nodes,
+            tags,
+            ways,
+            way_nodes,
+            relations,
+            relation_members,
+            ref_tags,
+            tag_values,
+            handles,
+            tables,
+            spool_files,
+            arguments: arguments.clone(),
+            database,
+            used_tags: HashMap::new(),
+            used_values: HashMap::new(),
+            locations: arguments
+                .geometry
+                .map(|_| NodeLocations::new(arguments.node_index)),
+            assembler,
+        }
+    }
+
+    /// Encodes the tags of the element for its tags column or sends them
+    /// to the tags and ref_tags tables.
+    fn tags(&mut self, main_info: &mut MainInfo, ref_type: &'static str) {
+        if let Some(key_filter) = &self.arguments.key_filter {
+            main_info.tags.retain(|(k, _)| key_filter.keeps(k));
+        }
+
+        if !self.arguments.tag_columns.is_empty() {
+            for (k, v) in std::mem::take(&mut main_info.tags) {
+                let value = self
+                    .arguments
+                    .tag_columns
+                    .iter()
+                    .find(|column| column.key == k)
+                    .and_then(|column| column.value(&v));
+                match value {
+                    Some(value) => main_info.tag_columns.push((k, value)),
+                    None => main_info.tags.push((k, v)),
+                }
+            }
+        }
+
+        if self.arguments.tag_storage != TagStorage::Table {
+            main_info.encoded_tags = Some(
+                self.arguments
+                    .tag_storage
+                    .encode(&main_info.tags, self.arguments.dialect),
+            );
+            return;
+        }
+
+        for (k, v) in std::mem::take(&mut main_info.tags) {
+            self.tag(k, v, main_info.id, ref_type);
+        }
+    }
+
+    fn tag(&mut self, k: String, v: String, ref_id: i64, ref_type: &'static str) {
+        let tag_id = match self.used_tags.get(&k) {
+            Some(id) => *id,
+            None => {
+                let id = i32::try_from(self.used_tags.len()).expect("Too many distinct tag keys.");
+                let in_tag = Tag {
+                    id,
+                    name: k.clone(),
+                };
+
+                self.used_tags.insert(k, id);
+                self.tags
+                    .as_ref()
+                    .unwrap()
+                    .send(ThreadSignal::Write(in_tag))
+                    .unwrap();
+                id
+            }
+        };
+
+        let used_values = &mut self.used_values;
+        let value_id = self.tag_values.as_ref().map(|tag_values| {
+            if let Some(id) = used_values.get(&v) {
+                return *id;
+            }
+
+            let id = i32::try_from(used_values.len()).expect("Too many distinct tag values.");
+            used_values.insert(v.clone(), id);
+            tag_values
+                .send(ThreadSignal::Write(TagValue {
+                    id,
+                    value: v.clone(),
+                }))
+                .unwrap();
+            id
+        });
+
+        self.ref_tags
+            .as_ref()
+            .unwrap()
+            .send(ThreadSignal::Write(UsedTag {
+                tag_id,
+                value: if value_id.is_some() { String::new() } else { v },
+                value_id,
+                ref_id,
+                ref_type: String::from(ref_type),
+            }))
+            .unwrap();
+    }
+
+    /// Stops all writer threads and waits until they flushed their files.
+    /// The spooled tables or the load script are written afterwards.
+    pub fn finish(self) {
+        if let Some(nodes) = self.nodes {
+            nodes.send(ThreadSignal::Stop).unwrap();
+        }
+        if let (Some(ways), Some(way_nodes)) = (self.ways, self.way_nodes) {
+            ways.send(ThreadSignal::Stop).unwrap();
+            way_nodes.send(ThreadSignal::Stop).unwrap();
+        }
+        if let (Some(relations), Some(relation_members)) = (self.relations, self.relation_members) {
+            relations.send(ThreadSignal::Stop).unwrap();
+            relation_members.send(ThreadSignal::Stop).unwrap();
+        }
+        if let Some(tags) = self.tags {
+            tags.send(ThreadSignal::Stop).unwrap();
+        }
+        if let Some(tag_values) = self.tag_values {
+            tag_values.send(ThreadSignal::Stop).unwrap();
+        }
+        if let Some(ref_tags) = self.ref_tags {
+            ref_tags.send(ThreadSignal::Stop).unwrap();
+        }
+
+        for handle in self.handles {
+            handle.join().unwrap();
+        }
+
+        write_spooled(self.spool_files);
+
+        if let Some(database) = self.database {
+            close_database(database);
+        }
+
+        if self.arguments.format == Format::Copy {
+            write_load_script(&self.arguments, &self.tables);
+        }
+    }
+}
+
+impl Handler for Converter {
+    fn node(&mut self, mut node: Node) {
+        if let Some(locations) = &mut self.locations {
+            locations.insert(&node);
+        }
+        if self.nodes.is_none() {
+            return;
+        }
+        self.tags(&mut node.main_info, "node");
+
+        self.nodes
+            .as_ref()
+            .unwrap()
+            .send(ThreadSignal::Write(node))
+            .unwrap();
+    }
+
+    fn way(&mut self, mut way: Way) {
+        if let Some(assembler) = &mut self.assembler {
+            assembler.way(&way);
+        }
+        if self.ways.is_none() {
+            return;
+        }
+        if let (Some(locations), Some(format)) = (&mut self.locations, self.arguments.geometry) {
+            way.geom = geometry::way_geometry(&way, locations, &self.arguments.area_tags)
+                .map(|geometry| geometry.encode(format));
+        }
+        self.tags(&mut way.main_info, "way");
+        let way_id = way.main_info.id;
+        let nodes = std::mem::take(&mut way.nodes);
+
+        self.ways
+            .as_ref()
+            .unwrap()
+            .send(ThreadSignal::Write(way))
+            .unwrap();
+        let way_nodes = self.way_nodes.as_ref().unwrap();
+        for (sequence_id, node_id) in nodes.into_iter().enumerate() {
+            way_nodes
+                .send(ThreadSignal::Write(WayNode {
+                    way_id,
+                    node_id,
+                    sequence_id: sequence_id as i32,
+                }))
+                .unwrap();
+        }
+    }
+
+    fn relation(&mut self, mut relation: Relation) {
+        if self.relations.is_none() {
+            return;
+        }
+        if let (Some(assembler), Some(locations), Some(format)) = (
+            &self.assembler,
+            &mut self.locations,
+            self.arguments.geometry,
+        ) {
+            match assembler.relation(&relation, locations) {
+                Ok(geometry) => relation.geom = geometry.map(|geometry| geometry.encode(format)),
+                Err(message) => eprintln!(
+                    "The relation {} is not a valid area: {}.",
+                    relation.main_info.id, message
+                ),
+            }
+        }
+        self.tags(&mut relation.main_info, "relation");
+        let members = std::mem::take(&mut relation.members);
+
+        self.relations
+            .as_ref()
+            .unwrap()
+            .send(ThreadSignal::Write(relation))
+            .unwrap();
+        let relation_members = self.relation_members.as_ref().unwrap();
+        for member in members {
+            relation_members.send(ThreadSignal::Write(member)).unwrap();
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::prelude::*;
+use std::path::PathBuf;
+
+use crate::dialect::Dialect;
+use crate::escape;
+use crate::models::*;
+use crate::writer::{Format, TableWriter};
+use crate::Arguments;
+
+/// Writes the rows of a table as a PostgreSQL COPY text format file.
+pub struct CopyWriter {
+    file: Box<dyn Write + Send>,
+    columns: Vec<String>,
+}
+
+impl CopyWriter {
+    pub fn new(file: Box<dyn Write + Send>, table: Table) -> CopyWriter {
+        CopyWriter {
+            file,
+            columns: table.get_columns(),
+        }
+    }
+}
+
+impl TableWriter for CopyWriter {
+    fn write_row(&mut self, data_set: &HashMap<&str, SqlType>) {
+        let mut line = String::new();
+        for (i, column) in self.columns.iter().enumerate() {
+            if i != 0 {
+                line.push('\t');
+            }
+
+            line += &match data_set.get(column.as_str()).unwrap_or(&SqlType::Null) {
+                SqlType::BigInt(big_int) => big_int.to_string(),
+                SqlType::Int(int) => int.to_string(),
+                SqlType::Decimal(coordinate) => coordinate.to_string(),
+                SqlType::Varchar(varchar) => escape::copy(varchar),
+                SqlType::Bool(b) => String::from(if *b { "t" } else { "f" }),
+                SqlType::Null => String::from("\\N"),
+            };
+        }
+        line.push('\n');
+
+        let w = write!(&mut self.file, "{}", line);
+        w.unwrap();
+    }
+
+    fn finish(&mut self) {
+        self.file.flush().unwrap();
+    }
+}
+
+/// Writes the `load.sql` psql script. It creates the tables, loads the data
+/// files in the import order and adds the constraints afterwards.
+pub fn write_load_script(arguments: &Arguments, tables: &[Table]) {
+    let dialect = Dialect::Postgres;
+    let ignore = !arguments.no_ignore;
+    let mut script = String::new();
+
+    for table in tables.iter() {
+        script += &dialect.create_table_without_constraints_query(table);
+    }
+
+    for table in tables.iter() {
+        script += &format!(
+            "\\copy {} ({}) FROM '{}.{}'\n",
+            table.name,
+            dialect.column_identifiers(table),
+            table.name,
+            Format::Copy.extension()
+        );
+    }
+
+    for table in tables.iter() {
+        // Duplicate rows are removed like INSERT IGNORE queries are doing.
+        if ignore && !table.has_serial_key() {
+            script += &format!(
+                "DELETE FROM {table} WHERE ctid IN (SELECT ctid FROM (SELECT ctid, row_number() OVER (PARTITION BY {key}) AS n FROM {table}) AS duplicates WHERE n > 1);\n",
+                table = table.name,
+                key = dialect.identifiers(&table.primary_key)
+            );
+        }
+        script += &dialect.add_primary_key_query(table);
+    }
+
+    for table in tables.iter() {
+        script += &dialect.finish_table_query(table, ignore);
+    }
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(PathBuf::from(&arguments.output).join("load.sql"))
+        .expect("Can not open the file load.sql. Already exist or permission denied");
+    file.write_all(script.as_bytes()).unwrap();
+}
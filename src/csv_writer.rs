@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::io::prelude::*;
+
+use crate::models::*;
+use crate::writer::TableWriter;
+
+/// Writes the rows of a table as RFC 4180 delimited text with a header line.
+///
+/// Null values are empty fields and empty strings are quoted, so both can be
+/// told apart. Booleans are `true` or `false`.
+pub struct CsvWriter {
+    file: Box<dyn Write + Send>,
+    columns: Vec<String>,
+    delimiter: char,
+}
+
+impl CsvWriter {
+    pub fn new(file: Box<dyn Write + Send>, table: Table, delimiter: char) -> CsvWriter {
+        let mut writer = CsvWriter {
+            file,
+            columns: table.get_columns(),
+            delimiter,
+        };
+
+        let header: Vec<String> = writer
+            .columns
+            .iter()
+            .map(|column| writer.field(column))
+            .collect();
+        writer.write_line(&header);
+
+        writer
+    }
+
+    /// Quotes the field if it contains the delimiter, quotes or line breaks.
+    fn field(&self, value: &str) -> String {
+        if value.is_empty() || value.contains([self.delimiter, '"', '\r', '\n']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            String::from(value)
+        }
+    }
+
+    fn write_line(&mut self, fields: &[String]) {
+        let w = write!(
+            &mut self.file,
+            "{}\r\n",
+            fields.join(&self.delimiter.to_string())
+        );
+        w.unwrap();
+    }
+}
+
+impl TableWriter for CsvWriter {
+    fn write_row(&mut self, data_set: &HashMap<&str, SqlType>) {
+        let fields: Vec<String> = self
+            .columns
+            .iter()
+            .map(
+                |column| match data_set.get(column.as_str()).unwrap_or(&SqlType::Null) {
+                    SqlType::BigInt(big_int) => big_int.to_string(),
+                    SqlType::Int(int) => int.to_string(),
+                    SqlType::Decimal(coordinate) => coordinate.to_string(),
+                    SqlType::Varchar(varchar) => self.field(varchar),
+                    SqlType::Bool(b) => b.to_string(),
+                    SqlType::Null => String::new(),
+                },
+            )
+            .collect();
+
+        self.write_line(&fields);
+    }
+
+    fn finish(&mut self) {
+        self.file.flush().unwrap();
+    }
+}
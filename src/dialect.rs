@@ -0,0 +1,305 @@
+use crate::escape;
+use crate::models::*;
+
+/// Column names that must be quoted in the dialect.
+const POSTGRES_RESERVED: [&str; 1] = ["user"];
+
+/// Maximum rows in a compound select of SQLite versions before 3.8.8.
+/// Multi row inserts are compound selects in those versions.
+const SQLITE_MAX_COMPOUND_SELECT: i32 = 500;
+
+/// Default maximum length of a SQLite statement in bytes.
+const SQLITE_MAX_SQL_LENGTH: usize = 1_000_000;
+
+/// SQL dialect of the output files.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dialect {
+    MySql,
+    Postgres,
+    Sqlite,
+}
+
+impl Dialect {
+    pub const NAMES: [&'static str; 3] = ["mysql", "postgres", "sqlite"];
+
+    pub fn from_name(name: &str) -> Option<Dialect> {
+        match name {
+            "mysql" => Some(Dialect::MySql),
+            "postgres" => Some(Dialect::Postgres),
+            "sqlite" => Some(Dialect::Sqlite),
+            _ => None,
+        }
+    }
+
+    fn column_type(&self, column_type: ColumnType) -> &'static str {
+        match (self, column_type) {
+            (Dialect::MySql, ColumnType::Serial) => "BIGINT AUTO_INCREMENT",
+            (Dialect::Postgres, ColumnType::Serial) => "BIGSERIAL",
+            // Only an INTEGER PRIMARY KEY column can be auto incremented.
+            (Dialect::Sqlite, ColumnType::Serial) => "INTEGER PRIMARY KEY AUTOINCREMENT",
+            (Dialect::Sqlite, ColumnType::BigInt) | (Dialect::Sqlite, ColumnType::Int) => "INTEGER",
+            (_, ColumnType::BigInt) => "BIGINT",
+            (_, ColumnType::Int) => "INTEGER",
+            (Dialect::Sqlite, ColumnType::Decimal) => "REAL",
+            (_, ColumnType::Decimal) => "DECIMAL(10,7)",
+            // Timestamps are written as they are in the input.
+            (Dialect::MySql, ColumnType::Varchar) | (Dialect::MySql, ColumnType::Timestamp) => {
+                "VARCHAR(256)"
+            }
+            (_, ColumnType::Varchar) | (_, ColumnType::Timestamp) => "TEXT",
+            (Dialect::MySql, ColumnType::Bool) => "TINYINT(2)",
+            (Dialect::Postgres, ColumnType::Bool) => "BOOLEAN",
+            (Dialect::Sqlite, ColumnType::Bool) => "INTEGER",
+            (Dialect::MySql, ColumnType::Json) => "JSON",
+            (Dialect::Postgres, ColumnType::Json) => "JSONB",
+            (Dialect::Postgres, ColumnType::Hstore) => "HSTORE",
+            // SQLite JSON functions are working on text.
+            (_, ColumnType::Json) | (_, ColumnType::Hstore) => "TEXT",
+            // Geometries are longer than the VARCHAR limit of MySQL.
+            (Dialect::MySql, ColumnType::Geometry) => "LONGTEXT",
+            (_, ColumnType::Geometry) => "TEXT",
+        }
+    }
+
+    /// Quotes the identifier if it is a reserved word or contains
+    /// characters other than lowercase letters, digits and underscores.
+    pub fn identifier(&self, name: &str) -> String {
+        let simple = name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            && !name.starts_with(|c: char| c.is_ascii_digit());
+
+        if !simple || (*self == Dialect::Postgres && POSTGRES_RESERVED.contains(&name)) {
+            self.quoted_identifier(name)
+        } else {
+            String::from(name)
+        }
+    }
+
+    fn quoted_identifier(&self, name: &str) -> String {
+        match self {
+            Dialect::MySql => format!("`{}`", name.replace('`', "``")),
+            Dialect::Postgres | Dialect::Sqlite => format!("\"{}\"", name.replace('"', "\"\"")),
+        }
+    }
+
+    fn column_identifier(&self, column: &Column) -> String {
+        if column.quoted {
+            self.quoted_identifier(&column.name)
+        } else {
+            self.identifier(&column.name)
+        }
+    }
+
+    /// Quoted names of the columns that are written. The serial column is
+    /// filled by the database.
+    pub fn column_identifiers(&self, table: &Table) -> String {
+        table
+            .columns
+            .iter()
+            .filter(|column| column.column_type != ColumnType::Serial)
+            .map(|column| self.column_identifier(column))
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    fn column_definitions(&self, table: &Table) -> Vec<String> {
+        table
+            .columns
+            .iter()
+            .map(|column| {
+                format!(
+                    "{} {}",
+                    self.column_identifier(column),
+                    self.column_type(column.column_type)
+                )
+            })
+            .collect()
+    }
+
+    /// Queries creating the extensions that the column types of the table
+    /// are depending on.
+    fn extensions(&self, table: &Table) -> &'static str {
+        let hstore = table
+            .columns
+            .iter()
+            .any(|column| column.column_type == ColumnType::Hstore);
+
+        if hstore && *self == Dialect::Postgres {
+            "CREATE EXTENSION IF NOT EXISTS hstore;\n"
+        } else {
+            ""
+        }
+    }
+
+    fn primary_key(&self, table: &Table) -> String {
+        format!(
+            "CONSTRAINT {}_pk PRIMARY KEY({})",
+            table.name,
+            self.identifiers(&table.primary_key)
+        )
+    }
+
+    pub fn create_table_query(&self, table: &Table) -> String {
+        let mut definitions = self.column_definitions(table);
+
+        // The serial column is already the primary key in SQLite.
+        if !(table.has_serial_key() && *self == Dialect::Sqlite) {
+            definitions.push(self.primary_key(table));
+        }
+
+        // PostgreSQL foreign keys are added after inserting the data.
+        if *self != Dialect::Postgres {
+            for foreign_key in table.foreign_keys.iter() {
+                definitions.push(self.foreign_key(foreign_key));
+            }
+        }
+
+        format!(
+            "{}CREATE TABLE {} ({}){};\n",
+            self.extensions(table),
+            table.name,
+            definitions.join(","),
+            // The default utf8 charset of MySQL can not store characters
+            // outside of the basic multilingual plane.
+            if *self == Dialect::MySql {
+                " DEFAULT CHARSET=utf8mb4"
+            } else {
+                ""
+            }
+        )
+    }
+
+    /// Create table query without the primary key and foreign keys. Used
+    /// when the constraints are added after loading the data.
+    pub fn create_table_without_constraints_query(&self, table: &Table) -> String {
+        format!(
+            "{}CREATE TABLE {} ({});\n",
+            self.extensions(table),
+            table.name,
+            self.column_definitions(table).join(",")
+        )
+    }
+
+    pub fn add_primary_key_query(&self, table: &Table) -> String {
+        format!(
+            "ALTER TABLE {} ADD {};\n",
+            table.name,
+            self.primary_key(table)
+        )
+    }
+
+    fn foreign_key(&self, foreign_key: &ForeignKey) -> String {
+        format!(
+            "CONSTRAINT {} FOREIGN KEY({}) REFERENCES {}(id)",
+            foreign_key.name,
+            self.identifier(foreign_key.column),
+            foreign_key.table
+        )
+    }
+
+    pub fn create_index_query(&self, table: &Table, index: &Index) -> String {
+        format!(
+            "CREATE INDEX {} ON {} ({});\n",
+            index.name,
+            table.name,
+            self.identifiers(&index.columns)
+        )
+    }
+
+    /// Queries that should be run after inserting all rows of the table.
+    /// Indexes are created after the rows, which is faster than updating
+    /// them on every insert.
+    ///
+    /// PostgreSQL can not ignore rows that are violating foreign keys. So the
+    /// constraints are not validated against the existing rows when ignoring
+    /// errors. Extracts are usually referring to elements out of the extract.
+    pub fn finish_table_query(&self, table: &Table, ignore: bool) -> String {
+        let mut query: String = table
+            .indexes
+            .iter()
+            .map(|index| self.create_index_query(table, index))
+            .collect();
+
+        if *self == Dialect::Postgres {
+            for foreign_key in table.foreign_keys.iter() {
+                query += &format!(
+                    "ALTER TABLE {} ADD {}{};\n",
+                    table.name,
+                    self.foreign_key(foreign_key),
+                    if ignore { " NOT VALID" } else { "" }
+                );
+            }
+        }
+
+        query
+    }
+
+    pub fn identifiers<S: AsRef<str>>(&self, names: &[S]) -> String {
+        names
+            .iter()
+            .map(|name| self.identifier(name.as_ref()))
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    /// Start of a multi row insert query up to the `VALUES` keyword.
+    pub fn insert_query_start(&self, table: &Table, ignore: bool) -> String {
+        format!(
+            "INSERT {}INTO {} ({}) VALUES ",
+            match self {
+                Dialect::MySql if ignore => "IGNORE ",
+                Dialect::Sqlite if ignore => "OR IGNORE ",
+                _ => "",
+            },
+            table.name,
+            self.column_identifiers(table)
+        )
+    }
+
+    /// Maximum rows in one insert query. The requested maximum is reduced
+    /// when the dialect can not handle that many rows.
+    pub fn maximum_rows(&self, requested: i32) -> i32 {
+        match self {
+            Dialect::Sqlite => requested.min(SQLITE_MAX_COMPOUND_SELECT),
+            _ => requested,
+        }
+    }
+
+    /// Maximum length of a query in bytes if the dialect has a limit.
+    pub fn maximum_query_length(&self) -> Option<usize> {
+        match self {
+            Dialect::Sqlite => Some(SQLITE_MAX_SQL_LENGTH),
+            _ => None,
+        }
+    }
+
+    /// End of a multi row insert query including the semicolon.
+    pub fn insert_query_end(&self, ignore: bool) -> &'static str {
+        if ignore && *self == Dialect::Postgres {
+            " ON CONFLICT DO NOTHING;\n"
+        } else {
+            ";\n"
+        }
+    }
+
+    pub fn value(&self, value: &SqlType) -> String {
+        match value {
+            SqlType::BigInt(big_int) => big_int.to_string(),
+            SqlType::Int(int) => int.to_string(),
+            SqlType::Decimal(coordinate) => coordinate.to_string(),
+            SqlType::Varchar(varchar) => match self {
+                Dialect::MySql => escape::mysql(varchar),
+                Dialect::Postgres => escape::postgres(varchar),
+                Dialect::Sqlite => escape::sqlite(varchar),
+            },
+            SqlType::Bool(b) => String::from(match (self, b) {
+                (Dialect::MySql, true) | (Dialect::Sqlite, true) => "1",
+                (Dialect::MySql, false) | (Dialect::Sqlite, false) => "0",
+                (Dialect::Postgres, true) => "TRUE",
+                (Dialect::Postgres, false) => "FALSE",
+            }),
+            SqlType::Null => String::from("NULL"),
+        }
+    }
+}
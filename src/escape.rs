@@ -0,0 +1,83 @@
+/// Replacement of NUL characters where the database can not store them.
+pub const NUL_REPLACEMENT: char = '\u{FFFD}';
+
+/// MySQL string literal. All characters that `mysql_real_escape_string`
+/// escapes are escaped with backslashes.
+pub fn mysql(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('\'');
+    for c in value.chars() {
+        match c {
+            '\0' => literal.push_str("\\0"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\u{1a}' => literal.push_str("\\Z"),
+            '\\' => literal.push_str("\\\\"),
+            '\'' => literal.push_str("\\'"),
+            '"' => literal.push_str("\\\""),
+            _ => literal.push(c),
+        }
+    }
+    literal.push('\'');
+    literal
+}
+
+/// PostgreSQL string literal. Escape string syntax is used when the value
+/// has backslashes, so the literal does not depend on the
+/// `standard_conforming_strings` setting. PostgreSQL can not store NUL
+/// characters in text, so they are replaced.
+pub fn postgres(value: &str) -> String {
+    let escape_syntax = value.contains('\\');
+    let mut literal = String::with_capacity(value.len() + 3);
+    if escape_syntax {
+        literal.push('E');
+    }
+    literal.push('\'');
+    for c in value.chars() {
+        match c {
+            '\0' => literal.push(NUL_REPLACEMENT),
+            '\'' => literal.push_str("''"),
+            '\\' => literal.push_str("\\\\"),
+            _ => literal.push(c),
+        }
+    }
+    literal.push('\'');
+    literal
+}
+
+/// SQLite string literal. SQLite is reading the SQL text as a C string and
+/// the sqlite3 shell is dropping carriage returns at line ends. So NUL and
+/// carriage return characters are concatenated with `char()`.
+pub fn sqlite(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('\'');
+    for c in value.chars() {
+        match c {
+            '\0' | '\r' => literal.push_str(&format!("'||char({})||'", c as u32)),
+            '\'' => literal.push_str("''"),
+            _ => literal.push(c),
+        }
+    }
+    literal.push('\'');
+    literal
+}
+
+/// Field of a PostgreSQL COPY text format file. PostgreSQL can not store
+/// NUL characters in text, so they are replaced.
+pub fn copy(value: &str) -> String {
+    let mut field = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\0' => field.push(NUL_REPLACEMENT),
+            '\\' => field.push_str("\\\\"),
+            '\n' => field.push_str("\\n"),
+            '\r' => field.push_str("\\r"),
+            '\t' => field.push_str("\\t"),
+            '\u{8}' => field.push_str("\\b"),
+            '\u{c}' => field.push_str("\\f"),
+            '\u{b}' => field.push_str("\\v"),
+            _ => field.push(c),
+        }
+    }
+    field
+}
@@ -0,0 +1,565 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::converter::Converter;
+use crate::filter::TagFilter;
+use crate::handler::Handler;
+use crate::input;
+use crate::models::*;
+use crate::poly::Polygon;
+use crate::Arguments;
+
+/// Box of the coordinates to extract. The borders are inside the box.
+#[derive(Clone, Copy, Debug)]
+pub struct Bbox {
+    pub min_lon: Coordinate,
+    pub min_lat: Coordinate,
+    pub max_lon: Coordinate,
+    pub max_lat: Coordinate,
+}
+
+impl Bbox {
+    /// Parses a box like `minlon,minlat,maxlon,maxlat`.
+    pub fn parse(value: &str) -> Result<Bbox, String> {
+        let coordinates: Vec<Coordinate> = value
+            .split(',')
+            .map(|coordinate| {
+                Coordinate::parse(coordinate.trim())
+                    .ok_or_else(|| format!("Invalid coordinate {} in the bbox.", coordinate))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let (min_lon, min_lat, max_lon, max_lat) = match coordinates[..] {
+            [min_lon, min_lat, max_lon, max_lat] => (min_lon, min_lat, max_lon, max_lat),
+            _ => {
+                return Err(String::from(
+                    "The bbox should be given as minlon,minlat,maxlon,maxlat.",
+                ))
+            }
+        };
+        if min_lon.0 > max_lon.0 || min_lat.0 > max_lat.0 {
+            return Err(String::from(
+                "The minimum coordinates of the bbox should not be greater than the maximum.",
+            ));
+        }
+
+        Ok(Bbox {
+            min_lon,
+            min_lat,
+            max_lon,
+            max_lat,
+        })
+    }
+
+    pub fn contains(&self, node: &Node) -> bool {
+        (self.min_lon.0..=self.max_lon.0).contains(&node.lng.0)
+            && (self.min_lat.0..=self.max_lat.0).contains(&node.lat.0)
+    }
+}
+
+/// Area of an extract.
+#[derive(Clone, Debug)]
+pub enum Region {
+    Bbox(Bbox),
+    Polygon(Arc<Polygon>),
+}
+
+impl Region {
+    pub fn contains(&self, node: &Node) -> bool {
+        match self {
+            Region::Bbox(bbox) => bbox.contains(node),
+            Region::Polygon(polygon) => polygon.contains(node),
+        }
+    }
+}
+
+/// Strategy to choose the ways and relations of an extract.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Strategy {
+    /// Ways and relations with a node inside the region. Nodes outside the
+    /// region are dropped from them. The input is read once.
+    Simple,
+    /// Like simple, but all nodes of the ways are kept.
+    CompleteWays,
+    /// Like complete ways, but all member ways of the multipolygon and
+    /// boundary relations are kept with their nodes.
+    Smart,
+}
+
+impl Strategy {
+    pub const NAMES: [&'static str; 3] = ["simple", "complete_ways", "smart"];
+
+    pub fn from_name(name: &str) -> Option<Strategy> {
+        match name {
+            "simple" => Some(Strategy::Simple),
+            "complete_ways" => Some(Strategy::CompleteWays),
+            "smart" => Some(Strategy::Smart),
+            _ => None,
+        }
+    }
+}
+
+/// Ids of the elements in the extract.
+#[derive(Default)]
+struct Selection {
+    nodes: HashSet<i64>,
+    ways: HashSet<i64>,
+    relations: HashSet<i64>,
+}
+
+impl Selection {
+    fn has_node(&self, way: &Way) -> bool {
+        way.nodes.iter().any(|node_id| self.nodes.contains(node_id))
+    }
+
+    fn elements(&mut self, ref_type: &str) -> &mut HashSet<i64> {
+        match ref_type {
+            "node" => &mut self.nodes,
+            "way" => &mut self.ways,
+            _ => &mut self.relations,
+        }
+    }
+
+    fn contains(&self, ref_type: &str, id: i64) -> bool {
+        match ref_type {
+            "node" => self.nodes.contains(&id),
+            "way" => self.ways.contains(&id),
+            _ => self.relations.contains(&id),
+        }
+    }
+
+    fn contains_member(&self, member: &RelationMember) -> bool {
+        self.contains(&member.ref_type, member.ref_id)
+    }
+
+    fn has_member(&self, relation: &Relation) -> bool {
+        relation
+            .members
+            .iter()
+            .any(|member| self.contains_member(member))
+    }
+}
+
+/// Whether the member ways of the relation are forming areas.
+pub(crate) fn is_area_relation(relation: &Relation) -> bool {
+    relation
+        .main_info
+        .tags
+        .iter()
+        .any(|(k, v)| k == "type" && (v == "multipolygon" || v == "boundary"))
+}
+
+/// First pass of the multi pass strategies. Selects the nodes in the
+/// region, the ways and relations having them and the nodes of the selected
+/// ways when the ways are complete.
+struct Scan<'a> {
+    region: &'a Region,
+    strategy: Strategy,
+    selection: Selection,
+    /// Nodes of the selected ways outside of the region.
+    way_nodes: HashSet<i64>,
+    /// Relations that are members of other relations.
+    sub_relations: Vec<(i64, i64)>,
+    /// Member ways of the area relations.
+    area_ways: HashMap<i64, Vec<i64>>,
+}
+
+impl Handler for Scan<'_> {
+    fn node(&mut self, node: Node) {
+        if self.region.contains(&node) {
+            self.selection.nodes.insert(node.main_info.id);
+        }
+    }
+
+    fn way(&mut self, way: Way) {
+        if self.selection.has_node(&way) {
+            self.selection.ways.insert(way.main_info.id);
+            if self.strategy != Strategy::Simple {
+                self.way_nodes.extend(way.nodes);
+            }
+        }
+    }
+
+    fn relation(&mut self, relation: Relation) {
+        let id = relation.main_info.id;
+        if self.selection.has_member(&relation) {
+            self.selection.relations.insert(id);
+        }
+
+        for member in relation.members.iter() {
+            if member.ref_type == "relation" {
+                self.sub_relations.push((id, member.ref_id));
+            }
+        }
+
+        if self.strategy == Strategy::Smart && is_area_relation(&relation) {
+            let ways = relation
+                .members
+                .iter()
+                .filter(|member| member.ref_type == "way")
+                .map(|member| member.ref_id)
+                .collect();
+            self.area_ways.insert(id, ways);
+        }
+    }
+}
+
+impl Scan<'_> {
+    /// Selects the relations having selected sub relations that are
+    /// coming after them in the input.
+    fn select_parent_relations(&mut self) {
+        loop {
+            let mut changed = false;
+            for (parent, child) in self.sub_relations.iter() {
+                if self.selection.relations.contains(child)
+                    && self.selection.relations.insert(*parent)
+                {
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    /// Member ways of the selected area relations that are not selected.
+    fn missing_area_ways(&self) -> HashSet<i64> {
+        self.area_ways
+            .iter()
+            .filter(|(id, _)| self.selection.relations.contains(id))
+            .flat_map(|(_, ways)| ways.iter())
+            .filter(|way_id| !self.selection.ways.contains(way_id))
+            .copied()
+            .collect()
+    }
+}
+
+/// Selects the ways and their nodes. Only the nodes in the extract are
+/// selected when an extract is given.
+struct WayNodes<'a> {
+    ways: HashSet<i64>,
+    selection: &'a mut Selection,
+    extract: Option<&'a Selection>,
+}
+
+impl Handler for WayNodes<'_> {
+    fn node(&mut self, _node: Node) {}
+
+    fn way(&mut self, way: Way) {
+        if self.ways.contains(&way.main_info.id) {
+            self.selection.ways.insert(way.main_info.id);
+            let extract = self.extract;
+            self.selection.nodes.extend(
+                way.nodes.into_iter().filter(|node_id| {
+                    extract.is_none_or(|extract| extract.nodes.contains(node_id))
+                }),
+            );
+        }
+    }
+
+    fn relation(&mut self, _relation: Relation) {}
+}
+
+/// Selects the node and way members of a relation in the extract. The
+/// member ways are added to the ways whose nodes are selected in another
+/// pass.
+fn select_members(
+    relation: &Relation,
+    extract: Option<&Selection>,
+    selection: &mut Selection,
+    member_ways: &mut HashSet<i64>,
+) {
+    for member in relation.members.iter() {
+        if extract.is_none_or(|extract| extract.contains_member(member)) {
+            selection.elements(&member.ref_type).insert(member.ref_id);
+            if member.ref_type == "way" {
+                member_ways.insert(member.ref_id);
+            }
+        }
+    }
+}
+
+/// Selects the elements kept by the tag filter and the elements that they
+/// are referring to. Only the elements in the extract are selected when an
+/// extract is given.
+struct Filter<'a> {
+    filter: &'a TagFilter,
+    extract: Option<&'a Selection>,
+    selection: Selection,
+    /// Relations kept by the filter. Their members are selected.
+    kept_relations: HashSet<i64>,
+    /// Relation members of every relation in the extract.
+    sub_relations: HashMap<i64, Vec<i64>>,
+    /// Member ways of the selected relations. Their nodes are selected in
+    /// another pass.
+    member_ways: HashSet<i64>,
+}
+
+impl Filter<'_> {
+    fn in_extract(&self, ref_type: &str, id: i64) -> bool {
+        self.extract
+            .is_none_or(|extract| extract.contains(ref_type, id))
+    }
+
+    /// Selects the relations that are members of the kept relations at any
+    /// depth and returns the ones whose members are not selected yet. The
+    /// visited relations are stopping the cycles.
+    fn select_sub_relations(&mut self) -> HashSet<i64> {
+        let mut pending: Vec<i64> = self
+            .selection
+            .relations
+            .iter()
+            .filter(|id| !self.kept_relations.contains(id))
+            .copied()
+            .collect();
+        let mut visited = HashSet::new();
+
+        while let Some(id) = pending.pop() {
+            if self.kept_relations.contains(&id) || !visited.insert(id) {
+                continue;
+            }
+            if let Some(children) = self.sub_relations.get(&id) {
+                pending.extend(children.iter().copied());
+            }
+        }
+
+        self.selection.relations.extend(visited.iter().copied());
+        visited
+    }
+}
+
+impl Handler for Filter<'_> {
+    fn node(&mut self, node: Node) {
+        if self.in_extract("node", node.main_info.id)
+            && self.filter.keeps('n', &node.main_info.tags)
+        {
+            self.selection.nodes.insert(node.main_info.id);
+        }
+    }
+
+    fn way(&mut self, way: Way) {
+        if self.in_extract("way", way.main_info.id) && self.filter.keeps('w', &way.main_info.tags) {
+            self.selection.ways.insert(way.main_info.id);
+            for node_id in way.nodes {
+                if self.in_extract("node", node_id) {
+                    self.selection.nodes.insert(node_id);
+                }
+            }
+        }
+    }
+
+    fn relation(&mut self, relation: Relation) {
+        let id = relation.main_info.id;
+        if !self.in_extract("relation", id) {
+            return;
+        }
+
+        let children: Vec<i64> = relation
+            .members
+            .iter()
+            .filter(|member| {
+                member.ref_type == "relation" && self.in_extract("relation", member.ref_id)
+            })
+            .map(|member| member.ref_id)
+            .collect();
+        if !children.is_empty() {
+            self.sub_relations.insert(id, children);
+        }
+
+        if self.filter.keeps('r', &relation.main_info.tags) {
+            self.selection.relations.insert(id);
+            self.kept_relations.insert(id);
+            select_members(
+                &relation,
+                self.extract,
+                &mut self.selection,
+                &mut self.member_ways,
+            );
+        }
+    }
+}
+
+/// Selects the members of the sub relations of the kept relations.
+struct SubRelationMembers<'a> {
+    relations: HashSet<i64>,
+    extract: Option<&'a Selection>,
+    selection: &'a mut Selection,
+    member_ways: &'a mut HashSet<i64>,
+}
+
+impl Handler for SubRelationMembers<'_> {
+    fn node(&mut self, _node: Node) {}
+
+    fn way(&mut self, _way: Way) {}
+
+    fn relation(&mut self, relation: Relation) {
+        if self.relations.contains(&relation.main_info.id) {
+            select_members(&relation, self.extract, self.selection, self.member_ways);
+        }
+    }
+}
+
+/// Passes the selected elements to the converter. Nodes, way nodes and
+/// members that are not selected are dropped, so every written row is
+/// referring to written rows. The elements are selected while reading
+/// when a region is given.
+struct Clip<'a> {
+    converter: &'a mut Converter,
+    selection: Selection,
+    region: Option<&'a Region>,
+}
+
+impl Handler for Clip<'_> {
+    fn node(&mut self, node: Node) {
+        if self.region.is_some_and(|region| region.contains(&node)) {
+            self.selection.nodes.insert(node.main_info.id);
+        }
+
+        if self.selection.nodes.contains(&node.main_info.id) {
+            self.converter.node(node);
+        }
+    }
+
+    fn way(&mut self, mut way: Way) {
+        if self.region.is_some() && self.selection.has_node(&way) {
+            self.selection.ways.insert(way.main_info.id);
+        }
+
+        if self.selection.ways.contains(&way.main_info.id) {
+            let nodes = &self.selection.nodes;
+            way.nodes.retain(|node_id| nodes.contains(node_id));
+            self.converter.way(way);
+        }
+    }
+
+    fn relation(&mut self, mut relation: Relation) {
+        if self.region.is_some() && self.selection.has_member(&relation) {
+            self.selection.relations.insert(relation.main_info.id);
+        }
+
+        if self.selection.relations.contains(&relation.main_info.id) {
+            let selection = &self.selection;
+            relation
+                .members
+                .retain(|member| selection.contains_member(member));
+            for (sequence_id, member) in relation.members.iter_mut().enumerate() {
+                member.sequence_id = sequence_id as i32;
+            }
+            self.converter.relation(relation);
+        }
+    }
+}
+
+/// Selects the elements in the region with the multi pass strategies.
+fn select_region(path: &Path, region: &Region, strategy: Strategy) -> Selection {
+    let mut scan = Scan {
+        region,
+        strategy,
+        selection: Selection::default(),
+        way_nodes: HashSet::new(),
+        sub_relations: vec![],
+        area_ways: HashMap::new(),
+    };
+    input::read(path, &mut scan);
+    scan.select_parent_relations();
+
+    let missing_ways = scan.missing_area_ways();
+    let mut selection = scan.selection;
+    selection.nodes.extend(scan.way_nodes);
+    if !missing_ways.is_empty() {
+        input::read(
+            path,
+            &mut WayNodes {
+                ways: missing_ways,
+                selection: &mut selection,
+                extract: None,
+            },
+        );
+    }
+
+    selection
+}
+
+/// Selects the elements kept by the tag filter with the elements that
+/// they are referring to. Sub relations are resolved at any depth.
+fn select_tags(path: &Path, filter: &TagFilter, extract: Option<&Selection>) -> Selection {
+    let mut tags = Filter {
+        filter,
+        extract,
+        selection: Selection::default(),
+        kept_relations: HashSet::new(),
+        sub_relations: HashMap::new(),
+        member_ways: HashSet::new(),
+    };
+    input::read(path, &mut tags);
+
+    let sub_relations = tags.select_sub_relations();
+    let mut selection = tags.selection;
+    let mut member_ways = tags.member_ways;
+    if !sub_relations.is_empty() {
+        input::read(
+            path,
+            &mut SubRelationMembers {
+                relations: sub_relations,
+                extract,
+                selection: &mut selection,
+                member_ways: &mut member_ways,
+            },
+        );
+    }
+    if !member_ways.is_empty() {
+        input::read(
+            path,
+            &mut WayNodes {
+                ways: member_ways,
+                selection: &mut selection,
+                extract,
+            },
+        );
+    }
+
+    selection
+}
+
+/// Reads the elements in the region and the elements kept by the tag
+/// filter and passes them to the converter. The input is read once with
+/// the simple strategy and without a tag filter. Otherwise the elements
+/// are selected in up to five passes before writing them.
+pub fn read(arguments: &Arguments, converter: &mut Converter) {
+    let path = Path::new(&arguments.input);
+    let region = arguments.region.as_ref();
+
+    if !is_multi_pass(arguments) {
+        let mut clip = Clip {
+            converter,
+            selection: Selection::default(),
+            region,
+        };
+        input::read(path, &mut clip);
+        return;
+    }
+
+    let extract = region.map(|region| select_region(path, region, arguments.strategy));
+    let selection = match &arguments.tag_filter {
+        Some(filter) => select_tags(path, filter, extract.as_ref()),
+        None => extract.unwrap_or_default(),
+    };
+
+    input::read(
+        path,
+        &mut Clip {
+            converter,
+            selection,
+            region: None,
+        },
+    );
+}
+
+/// Whether the input is read more than once for the region and the tag
+/// filter of the arguments.
+pub fn is_multi_pass(arguments: &Arguments) -> bool {
+    arguments.tag_filter.is_some()
+        || (arguments.region.is_some() && arguments.strategy != Strategy::Simple)
+}
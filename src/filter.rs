@@ -0,0 +1,159 @@
+/// Tag expression of a filter like `w/highway=primary,secondary`.
+#[derive(Clone, Debug)]
+struct Expression {
+    /// Element types of the expression as `n`, `w` and `r` characters.
+    types: String,
+    /// Key of the tag. Can have `*` wildcards.
+    key: String,
+    /// Values of the tag. Any value is matching when there are no values.
+    values: Vec<String>,
+    /// Whether the tag should have a value other than the values.
+    negated: bool,
+}
+
+impl Expression {
+    /// Parses an expression like `[nwr]/key`, `[nwr]/key=value,value` or
+    /// `[nwr]/key!=value`. Expressions without types are matching all
+    /// element types.
+    fn parse(expression: &str) -> Result<Expression, String> {
+        let (types, tag) = match expression.find('/') {
+            Some(position)
+                if position > 0
+                    && expression[..position]
+                        .chars()
+                        .all(|c| c == 'n' || c == 'w' || c == 'r') =>
+            {
+                (&expression[..position], &expression[position + 1..])
+            }
+            _ => ("nwr", expression),
+        };
+
+        let (key, values, negated) = match tag.find("!=") {
+            Some(position) => (&tag[..position], Some(&tag[position + 2..]), true),
+            None => match tag.find('=') {
+                Some(position) => (&tag[..position], Some(&tag[position + 1..]), false),
+                None => (tag, None, false),
+            },
+        };
+        if key.is_empty() {
+            return Err(format!("The filter expression {} has no key.", expression));
+        }
+
+        let values: Vec<String> = match values {
+            Some("*") | None => vec![],
+            Some(values) => values.split(',').map(String::from).collect(),
+        };
+        if negated && values.is_empty() {
+            return Err(format!(
+                "The filter expression {} has no values.",
+                expression
+            ));
+        }
+
+        Ok(Expression {
+            types: String::from(types),
+            key: String::from(key),
+            values,
+            negated,
+        })
+    }
+
+    fn matches(&self, element_type: char, tags: &[(String, String)]) -> bool {
+        self.types.contains(element_type)
+            && tags.iter().any(|(k, v)| {
+                glob_match(&self.key, k)
+                    && (self.values.is_empty() || self.values.contains(v) != self.negated)
+            })
+    }
+}
+
+/// Chooses the elements by their tags. An element is kept when it is
+/// matching one of the keep expressions and none of the drop expressions.
+/// All elements are matching when there are no keep expressions.
+#[derive(Clone, Debug)]
+pub struct TagFilter {
+    keep: Vec<Expression>,
+    drop: Vec<Expression>,
+}
+
+impl TagFilter {
+    /// Parses the expressions of the keep and drop options. Expressions
+    /// in a value are separated by whitespace.
+    pub fn parse(keep: &[&str], drop: &[&str]) -> Result<TagFilter, String> {
+        let parse = |values: &[&str]| -> Result<Vec<Expression>, String> {
+            values
+                .iter()
+                .flat_map(|value| value.split_whitespace())
+                .map(Expression::parse)
+                .collect()
+        };
+
+        Ok(TagFilter {
+            keep: parse(keep)?,
+            drop: parse(drop)?,
+        })
+    }
+
+    /// Whether the element of the type `n`, `w` or `r` with the tags is
+    /// kept.
+    pub fn keeps(&self, element_type: char, tags: &[(String, String)]) -> bool {
+        (self.keep.is_empty()
+            || self
+                .keep
+                .iter()
+                .any(|expression| expression.matches(element_type, tags)))
+            && !self
+                .drop
+                .iter()
+                .any(|expression| expression.matches(element_type, tags))
+    }
+}
+
+/// Chooses the tag keys that are written. A key is written when it is
+/// matching one of the keep patterns and none of the drop patterns. All
+/// keys are matching when there are no keep patterns.
+#[derive(Clone, Debug)]
+pub struct KeyFilter {
+    keep: Vec<String>,
+    drop: Vec<String>,
+}
+
+impl KeyFilter {
+    /// Parses comma separated lists of key patterns like `tiger:*,source*`.
+    pub fn parse(keep: Option<&str>, drop: Option<&str>) -> KeyFilter {
+        let parse = |patterns: Option<&str>| -> Vec<String> {
+            patterns
+                .unwrap_or("")
+                .split(',')
+                .map(str::trim)
+                .filter(|pattern| !pattern.is_empty())
+                .map(String::from)
+                .collect()
+        };
+
+        KeyFilter {
+            keep: parse(keep),
+            drop: parse(drop),
+        }
+    }
+
+    /// Whether the tag with the key is written.
+    pub fn keeps(&self, key: &str) -> bool {
+        (self.keep.is_empty() || self.keep.iter().any(|pattern| glob_match(pattern, key)))
+            && !self.drop.iter().any(|pattern| glob_match(pattern, key))
+    }
+}
+
+/// Matches a text against a pattern where `*` is matching any characters.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    match pattern.find('*') {
+        None => pattern == text,
+        Some(position) => {
+            let (prefix, rest) = (&pattern[..position], &pattern[position + 1..]);
+            text.starts_with(prefix)
+                && (prefix.len()..=text.len())
+                    .filter(|start| text.is_char_boundary(*start))
+                    .any(|start| glob_match(rest, &text[start..]))
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+use std::env::temp_dir;
+use std::fs::{self, File, OpenOptions};
+use std::io::{prelude::*, BufWriter, SeekFrom};
+use std::path::PathBuf;
+use std::process;
+
+use crate::filter::TagFilter;
+use crate::models::{Coordinate, Node, Way};
+
+/// Encoding of the geometry columns.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GeometryFormat {
+    /// Well-known text like `LINESTRING(1 2,3 4)`.
+    Wkt,
+    /// Well-known binary in little endian byte order as a hex string.
+    Wkb,
+}
+
+impl GeometryFormat {
+    pub const NAMES: [&'static str; 2] = ["wkt", "wkb"];
+
+    pub fn from_name(name: &str) -> Option<GeometryFormat> {
+        match name {
+            "wkt" => Some(GeometryFormat::Wkt),
+            "wkb" => Some(GeometryFormat::Wkb),
+            _ => None,
+        }
+    }
+}
+
+/// Storage of the node locations.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IndexType {
+    /// Hash map of the locations. Needs around 40 bytes per node.
+    Memory,
+    /// Sparse temporary file with eight bytes per node id.
+    Disk,
+}
+
+impl IndexType {
+    pub const NAMES: [&'static str; 2] = ["memory", "disk"];
+
+    pub fn from_name(name: &str) -> Option<IndexType> {
+        match name {
+            "memory" => Some(IndexType::Memory),
+            "disk" => Some(IndexType::Disk),
+            _ => None,
+        }
+    }
+}
+
+/// Tag expressions of the closed ways that are areas. Closed ways tagged
+/// with `area=no` are never areas.
+pub const DEFAULT_AREA_TAGS: &str = "area=yes building building:part landuse amenity leisure \
+     shop tourism historic place natural!=coastline,cliff,ridge,arete,tree_row \
+     man_made!=embankment,breakwater,groyne,pipeline,cutline \
+     aeroway!=runway,taxiway,jet_bridge,parking_position waterway=riverbank,dock,boatyard,dam \
+     power=plant,substation,generator,transformer";
+
+/// Longitude and latitude of a node.
+pub type Point = (Coordinate, Coordinate);
+
+/// Geometry of an element. Rings are closed, so their last point is the
+/// same as the first one.
+#[derive(Debug, PartialEq)]
+pub enum Geometry {
+    LineString(Vec<Point>),
+    /// Outer ring followed by the holes.
+    Polygon(Vec<Vec<Point>>),
+    MultiPolygon(Vec<Vec<Vec<Point>>>),
+}
+
+impl Geometry {
+    pub fn encode(&self, format: GeometryFormat) -> String {
+        match format {
+            GeometryFormat::Wkt => self.wkt(),
+            GeometryFormat::Wkb => {
+                let mut wkb = vec![];
+                self.write_wkb(&mut wkb);
+                wkb.iter().map(|byte| format!("{:02X}", byte)).collect()
+            }
+        }
+    }
+
+    fn wkt(&self) -> String {
+        let points = |points: &[Point]| -> String {
+            let points: Vec<String> = points
+                .iter()
+                .map(|(lng, lat)| format!("{} {}", lng, lat))
+                .collect();
+            format!("({})", points.join(","))
+        };
+        let rings = |rings: &[Vec<Point>]| -> String {
+            let rings: Vec<String> = rings.iter().map(|ring| points(ring)).collect();
+            format!("({})", rings.join(","))
+        };
+
+        match self {
+            Geometry::LineString(line) => format!("LINESTRING{}", points(line)),
+            Geometry::Polygon(polygon) => format!("POLYGON{}", rings(polygon)),
+            Geometry::MultiPolygon(polygons) => {
+                let polygons: Vec<String> = polygons.iter().map(|polygon| rings(polygon)).collect();
+                format!("MULTIPOLYGON({})", polygons.join(","))
+            }
+        }
+    }
+
+    fn write_wkb(&self, wkb: &mut Vec<u8>) {
+        let points = |wkb: &mut Vec<u8>, points: &[Point]| {
+            wkb.extend_from_slice(&(points.len() as u32).to_le_bytes());
+            for (lng, lat) in points.iter() {
+                wkb.extend_from_slice(&lng.to_f64().to_le_bytes());
+                wkb.extend_from_slice(&lat.to_f64().to_le_bytes());
+            }
+        };
+        let rings = |wkb: &mut Vec<u8>, rings: &[Vec<Point>]| {
+            wkb.extend_from_slice(&(rings.len() as u32).to_le_bytes());
+            for ring in rings.iter() {
+                points(wkb, ring);
+            }
+        };
+
+        // Little endian byte order and the geometry type.
+        wkb.push(1);
+        match self {
+            Geometry::LineString(line) => {
+                wkb.extend_from_slice(&2u32.to_le_bytes());
+                points(wkb, line);
+            }
+            Geometry::Polygon(polygon) => {
+                wkb.extend_from_slice(&3u32.to_le_bytes());
+                rings(wkb, polygon);
+            }
+            // Every polygon has its own byte order and type.
+            Geometry::MultiPolygon(polygons) => {
+                wkb.extend_from_slice(&6u32.to_le_bytes());
+                wkb.extend_from_slice(&(polygons.len() as u32).to_le_bytes());
+                for polygon in polygons.iter() {
+                    Geometry::Polygon(polygon.clone()).write_wkb(wkb);
+                }
+            }
+        }
+    }
+}
+
+/// Whether the tags of a closed way are describing an area.
+pub fn is_area(tags: &[(String, String)], area_tags: &TagFilter) -> bool {
+    !tags.iter().any(|(k, v)| k == "area" && v == "no") && area_tags.keeps('w', tags)
+}
+
+/// Builds the geometry of a way from the locations of its nodes. Closed
+/// ways with area tags are polygons and other ways are line strings. Nodes
+/// without a location are left out of line strings. Ways with less than
+/// two located nodes have no geometry.
+pub fn way_geometry(
+    way: &Way,
+    locations: &mut NodeLocations,
+    area_tags: &TagFilter,
+) -> Option<Geometry> {
+    let points: Vec<Point> = way
+        .nodes
+        .iter()
+        .filter_map(|node_id| locations.get(*node_id))
+        .collect();
+    if points.len() < 2 {
+        return None;
+    }
+
+    let closed = way.nodes.len() >= 4
+        && way.nodes.first() == way.nodes.last()
+        && points.len() == way.nodes.len();
+    if closed && is_area(&way.main_info.tags, area_tags) {
+        Some(Geometry::Polygon(vec![points]))
+    } else {
+        Some(Geometry::LineString(points))
+    }
+}
+
+/// Locations of the nodes by their ids.
+pub enum NodeLocations {
+    Memory(HashMap<i64, Point>),
+    Disk(DiskIndex),
+}
+
+impl NodeLocations {
+    pub fn new(index_type: IndexType) -> NodeLocations {
+        match index_type {
+            IndexType::Memory => NodeLocations::Memory(HashMap::new()),
+            IndexType::Disk => NodeLocations::Disk(DiskIndex::new()),
+        }
+    }
+
+    pub fn insert(&mut self, node: &Node) {
+        match self {
+            NodeLocations::Memory(locations) => {
+                locations.insert(node.main_info.id, (node.lng, node.lat));
+            }
+            NodeLocations::Disk(index) => index.insert(node.main_info.id, (node.lng, node.lat)),
+        }
+    }
+
+    pub fn get(&mut self, id: i64) -> Option<Point> {
+        match self {
+            NodeLocations::Memory(locations) => locations.get(&id).copied(),
+            NodeLocations::Disk(index) => index.get(id),
+        }
+    }
+}
+
+/// Bytes of a page that is read at once from the disk index.
+const PAGE_SIZE: u64 = 4096;
+
+/// Node locations in a sparse temporary file. The location of a node is at
+/// eight times its id. Coordinates are stored in offset binary, so the
+/// zeros of the unwritten parts are out of the range of the coordinates.
+pub struct DiskIndex {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    /// Position of the writer. Nodes are usually sorted by their ids, so
+    /// the writer only seeks on gaps between the ids.
+    position: u64,
+    /// Whether the writer has locations that the reader can not see yet.
+    unflushed: bool,
+    reader: File,
+    /// Number and bytes of the last read page.
+    page: Option<(u64, Vec<u8>)>,
+    /// Negative ids that are used by editors for new nodes.
+    negative: HashMap<i64, Point>,
+}
+
+impl DiskIndex {
+    fn new() -> DiskIndex {
+        let path = temp_dir().join(format!("osm-to-sql-{}-node-locations", process::id()));
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .unwrap_or_else(|e| panic!("Can not create the node index {:?}: {}", path, e));
+        let reader = File::open(&path).unwrap();
+
+        DiskIndex {
+            path,
+            writer: BufWriter::new(file),
+            position: 0,
+            unflushed: false,
+            reader,
+            page: None,
+            negative: HashMap::new(),
+        }
+    }
+
+    fn insert(&mut self, id: i64, (lng, lat): Point) {
+        if id < 0 {
+            self.negative.insert(id, (lng, lat));
+            return;
+        }
+
+        let offset = id as u64 * 8;
+        if offset != self.position {
+            self.writer.seek(SeekFrom::Start(offset)).unwrap();
+        }
+        let mut bytes = [0; 8];
+        bytes[..4].copy_from_slice(&encode(lng));
+        bytes[4..].copy_from_slice(&encode(lat));
+        self.writer
+            .write_all(&bytes)
+            .expect("Can not write to the node index.");
+        self.position = offset + 8;
+        self.unflushed = true;
+    }
+
+    fn get(&mut self, id: i64) -> Option<Point> {
+        if id < 0 {
+            return self.negative.get(&id).copied();
+        }
+
+        if self.unflushed {
+            self.writer
+                .flush()
+                .expect("Can not write to the node index.");
+            self.unflushed = false;
+            self.page = None;
+        }
+
+        let offset = id as u64 * 8;
+        let number = offset / PAGE_SIZE;
+        if self.page.as_ref().is_none_or(|(page, _)| *page != number) {
+            let mut bytes = Vec::with_capacity(PAGE_SIZE as usize);
+            self.reader
+                .seek(SeekFrom::Start(number * PAGE_SIZE))
+                .unwrap();
+            (&mut self.reader)
+                .take(PAGE_SIZE)
+                .read_to_end(&mut bytes)
+                .expect("Can not read the node index.");
+            bytes.resize(PAGE_SIZE as usize, 0);
+            self.page = Some((number, bytes));
+        }
+
+        let (_, page) = self.page.as_ref().unwrap();
+        let start = (offset % PAGE_SIZE) as usize;
+        let lng = decode(&page[start..start + 4]);
+        let lat = decode(&page[start + 4..start + 8]);
+        lng.zip(lat)
+    }
+}
+
+impl Drop for DiskIndex {
+    fn drop(&mut self) {
+        // The index is a temporary file even if the conversion failed.
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn encode(coordinate: Coordinate) -> [u8; 4] {
+    (coordinate.0 as u32 ^ 0x8000_0000).to_le_bytes()
+}
+
+/// Decodes a coordinate. Unwritten zeros are `None`.
+fn decode(bytes: &[u8]) -> Option<Coordinate> {
+    let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    if value == 0 {
+        None
+    } else {
+        Some(Coordinate((value ^ 0x8000_0000) as i32))
+    }
+}
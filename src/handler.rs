@@ -0,0 +1,11 @@
+use crate::models::{Node, Relation, Way};
+
+/// Receives the elements of an input file in the order of the file.
+/// Elements are passed with their tags, way nodes and relation members.
+pub trait Handler {
+    fn node(&mut self, node: Node);
+
+    fn way(&mut self, way: Way);
+
+    fn relation(&mut self, relation: Relation);
+}
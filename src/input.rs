@@ -0,0 +1,104 @@
+use bzip2::read::MultiBzDecoder;
+use flate2::read::MultiGzDecoder;
+use quick_xml::Reader;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+use crate::handler::Handler;
+use crate::{pbf, xml};
+
+#[derive(Debug)]
+pub enum InputFormat {
+    Xml,
+    Pbf,
+}
+
+impl InputFormat {
+    /// Detects the format from the file extension. Falls back to the magic
+    /// bytes at the start of the file when the extension is not known.
+    pub fn detect(path: &Path, start: &[u8]) -> InputFormat {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("pbf") => InputFormat::Pbf,
+            Some("osm") | Some("xml") => InputFormat::Xml,
+            _ => {
+                if pbf::is_pbf(start) {
+                    InputFormat::Pbf
+                } else {
+                    InputFormat::Xml
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Compression {
+    None,
+    Bzip2,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Detects the compression from the magic bytes at the start of the file.
+    pub fn detect(start: &[u8]) -> Compression {
+        if start.starts_with(b"BZh") {
+            Compression::Bzip2
+        } else if start.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if start.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+
+    /// Wraps the input with a streaming decoder of this compression.
+    pub fn decoder<'a, R: BufRead + 'a>(&self, input: R) -> io::Result<Box<dyn BufRead + 'a>> {
+        Ok(match self {
+            Compression::None => Box::new(input),
+            Compression::Bzip2 => Box::new(BufReader::new(MultiBzDecoder::new(input))),
+            Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(input))),
+            Compression::Zstd => Box::new(BufReader::new(zstd::Decoder::with_buffer(input)?)),
+        })
+    }
+}
+
+/// Reads the input file and passes every element to the handler. The
+/// standard input is read when the path is `-`.
+pub fn read(path: &Path, handler: &mut dyn Handler) {
+    let source: Box<dyn Read> = if path == Path::new("-") {
+        Box::new(io::stdin())
+    } else {
+        Box::new(File::open(path).unwrap_or_else(|e| panic!("Invalid file :- {:?}", e)))
+    };
+    let mut input = BufReader::new(source);
+    let start = input
+        .fill_buf()
+        .unwrap_or_else(|e| panic!("Invalid file :- {:?}", e));
+
+    let compression = Compression::detect(start);
+    let mut input = compression
+        .decoder(input)
+        .unwrap_or_else(|e| panic!("Can not decompress the file :- {:?}", e));
+
+    // The format of compressed files is shown by the extension before the
+    // compression extension. Eg:- planet.osm.bz2
+    let path = if compression == Compression::None {
+        path
+    } else {
+        path.file_stem().map(Path::new).unwrap_or(path)
+    };
+
+    let start = input
+        .fill_buf()
+        .unwrap_or_else(|e| panic!("Can not decompress the file :- {:?}", e));
+
+    match InputFormat::detect(path, start) {
+        InputFormat::Xml => xml::read(Reader::from_reader(input), handler),
+        InputFormat::Pbf => {
+            pbf::read(input, handler).unwrap_or_else(|e| panic!("Invalid PBF file :- {}", e))
+        }
+    }
+}
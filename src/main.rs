@@ -1,23 +1,37 @@
 use clap::{App, Arg};
-use quick_xml::events::Event;
-use quick_xml::Reader;
-use std::collections::HashMap;
-use std::fs::OpenOptions;
-use std::io::prelude::*;
-use std::path::{Path, PathBuf};
-use std::str;
-use std::sync::mpsc::{channel, Sender};
-use std::thread::spawn;
-use std::thread::JoinHandle;
-use std::time::Duration;
-
+use std::path::Path;
+use std::sync::Arc;
+
+pub mod converter;
+pub mod copy_writer;
+pub mod csv_writer;
+pub mod dialect;
+pub mod escape;
+pub mod extract;
+pub mod filter;
+pub mod geometry;
+pub mod handler;
+pub mod input;
 pub mod models;
-use models::*;
-
-enum ThreadSignal<T: Model> {
-    Write(T),
-    Stop,
-}
+pub mod multipolygon;
+pub mod parquet_writer;
+pub mod pbf;
+pub mod poly;
+pub mod sql_writer;
+pub mod sqlite_writer;
+pub mod tags;
+pub mod writer;
+pub mod xml;
+
+use converter::Converter;
+use dialect::Dialect;
+use extract::{Bbox, Region, Strategy};
+use filter::{KeyFilter, TagFilter};
+use geometry::{GeometryFormat, IndexType, DEFAULT_AREA_TAGS};
+use models::ElementType;
+use poly::Polygon;
+use tags::{TagColumn, TagStorage};
+use writer::Format;
 
 #[derive(Clone, Debug)]
 pub struct Arguments {
@@ -25,92 +39,30 @@ pub struct Arguments {
     pub output: String,
     pub maximum_rows: i32,
     pub no_ignore: bool,
-}
-
-fn new_thread<'a, T: Model + Send + 'static>(arguments: Arguments) ->( JoinHandle<()>,Sender<ThreadSignal<T>>) {
-    let (snd, rcv) = channel::<ThreadSignal<T>>();
-    let handle = spawn(move || {
-        let mut count = 0;
-        let file_name = format!("{}.sql", T::get_table_name());
-        let file_path = PathBuf::from(arguments.output).join(file_name);
-
-        let file = OpenOptions::new()
-            .write(true)
-            .append(true)
-            .create_new(true)
-            .open(file_path)
-            .expect(&format!(
-                "Can not open the file {}. Already exist or permission denied",
-                T::get_table_name()
-            ));
-
-        let w = write!(&file, "{}", T::get_create_table_query());
-        w.unwrap();
-
-        loop {
-            match rcv.recv_timeout(Duration::from_secs(1)) {
-                Ok(result) => match result {
-                    ThreadSignal::Write(entry) => {
-                        count += 1;
-
-                        let data_set = entry.get_data_set();
-                        let columns = T::get_columns();
-                        if count > arguments.maximum_rows || count == 1 {
-                            let columns_str =
-                                columns.iter().fold(String::new(), |a, b| a + b + ",");
-                            let columns_str = columns_str.trim_end_matches(",");
-                            let w = write!(
-                                &file,
-                                ";\nINSERT {} INTO {} ({}) VALUES ",
-                                if arguments.no_ignore { "" } else { "IGNORE" },
-                                T::get_table_name(),
-                                columns_str
-                            );
-                            w.unwrap();
-
-                            if count>arguments.maximum_rows {
-                                count = 1;
-                            }
-
-                        } else {
-                            let w = write!(&file, ",");
-                            w.unwrap()
-                        }
-
-                        let mut values = String::new();
-                        for (i, column) in columns.iter().enumerate() {
-                            if i != 0 {
-                                values += ","
-                            }
-
-                            let value = data_set.get(column).unwrap_or(&SqlType::Null);
-                            values += &match value {
-                                SqlType::BigInt(big_int) => big_int.to_string(),
-                                SqlType::Int(int) => int.to_string(),
-                                SqlType::Decimal(dec) => dec.to_string(),
-                                SqlType::Varchar(varchar) => {
-                                    let new_varchar = String::from("\"") + varchar;
-                                    let new_varchar = new_varchar + &String::from("\"");
-                                    new_varchar
-                                }
-                                SqlType::Bool(b) => String::from(if b.clone() { "1" } else { "0" }),
-                                SqlType::Null => String::from("NULL"),
-                            };
-                        }
-
-                        let w = write!(&file, "({})", values);
-                        w.unwrap()
-                    }
-                    ThreadSignal::Stop => {
-                        break;
-                    }
-                },
-                Err(_) => {}
-            }
-        }
-    });
-
-    (handle,snd)
+    pub row_group_size: usize,
+    pub normalize_values: bool,
+    pub tag_storage: TagStorage,
+    pub tag_columns: Vec<TagColumn>,
+    /// Area of the extract. All elements are written without a region.
+    pub region: Option<Region>,
+    pub strategy: Strategy,
+    /// Filter of the elements by their tags.
+    pub tag_filter: Option<TagFilter>,
+    /// Filter of the written tag keys.
+    pub key_filter: Option<KeyFilter>,
+    /// Element types that are written to their tables.
+    pub types: Vec<ElementType>,
+    /// Whether the version, changeset, user, uid, visible and timestamp
+    /// columns are left out.
+    pub no_metadata: bool,
+    /// Encoding of the way geometries. No geometries are written without
+    /// it.
+    pub geometry: Option<GeometryFormat>,
+    pub node_index: IndexType,
+    /// Tag expressions of the closed ways that are polygons.
+    pub area_tags: TagFilter,
+    pub dialect: Dialect,
+    pub format: Format,
 }
 
 fn main() {
@@ -125,16 +77,24 @@ fn main() {
                 .value_name("FILE")
                 .required(true)
                 .takes_value(true)
-                .help("Sets the input OSM XML file."),
+                .help("Sets the input OSM XML or PBF file. XML files can be bzip2, gzip or zstd compressed. Use - to read from the standard input."),
         )
         .arg(
             Arg::with_name("output")
                 .short("d")
                 .long("directory")
                 .value_name("DIRECTORY")
-                .required(true)
+                .required_unless("database")
                 .takes_value(true)
-                .help("Output directory. This directory should be an empty directory."),
+                .help("Output directory. This directory should be an empty directory. Use - to write all tables to the standard output."),
+        )
+        .arg(
+            Arg::with_name("database")
+                .long("database")
+                .value_name("FILE")
+                .takes_value(true)
+                .conflicts_with("output")
+                .help("Inserts the rows into a SQLite database instead of writing files. The database is created if it does not exist."),
         )
         .arg(
             Arg::with_name("rows")
@@ -145,195 +105,336 @@ fn main() {
                 .default_value("400")
                 .help("Maximum rows per one SQL insert query. Default is 400"),
         )
+        .arg(
+            Arg::with_name("row-group-size")
+                .long("row-group-size")
+                .value_name("ROWS")
+                .takes_value(true)
+                .default_value("100000")
+                .help("Maximum rows per one row group of the parquet files."),
+        )
         .arg(
             Arg::with_name("no-ignore")
                 .short("g")
                 .long("no-ignore")
                 .help("Do not use INSERT IGNORE queries."),
         )
+        .arg(
+            Arg::with_name("normalize-values")
+                .long("normalize-values")
+                .help("Writes every distinct tag value once to the tag_values table. The ref_tags table is referring to them with the value_id column."),
+        )
+        .arg(
+            Arg::with_name("tags")
+                .long("tags")
+                .value_name("STORAGE")
+                .takes_value(true)
+                .possible_values(&TagStorage::NAMES)
+                .default_value("table")
+                .help("Storage of the tags. The json and hstore storages are writing the tags to a tags column of the nodes, ways and relations instead of the tags and ref_tags tables. JSON is stored as jsonb in PostgreSQL. The hstore storage is only available for the postgres dialect."),
+        )
+        .arg(
+            Arg::with_name("columns")
+                .long("columns")
+                .value_name("KEYS")
+                .takes_value(true)
+                .help("Comma separated tag keys that are written to their own columns of the nodes, ways and relations. The type of a column can be given like layer=integer. Possible types are text and integer. Values that are not valid for the type are left in the tags."),
+        )
+        .arg(
+            Arg::with_name("bbox")
+                .long("bbox")
+                .value_name("MINLON,MINLAT,MAXLON,MAXLAT")
+                .takes_value(true)
+                .allow_hyphen_values(true)
+                .help("Writes only the nodes in the box and the ways and relations having them."),
+        )
+        .arg(
+            Arg::with_name("polygon")
+                .long("polygon")
+                .value_name("FILE")
+                .takes_value(true)
+                .conflicts_with("bbox")
+                .help("Writes only the nodes in the area of an Osmosis polygon file and the ways and relations having them."),
+        )
+        .arg(
+            Arg::with_name("strategy")
+                .long("strategy")
+                .alias("bbox-strategy")
+                .value_name("STRATEGY")
+                .takes_value(true)
+                .possible_values(&Strategy::NAMES)
+                .default_value("complete_ways")
+                .help("Strategy to choose the ways and relations in the box or polygon. The simple strategy is dropping the nodes outside of the area from the ways. The complete_ways strategy is keeping all nodes of the ways. The smart strategy is also keeping all member ways of multipolygon and boundary relations. The input is read more than once except with the simple strategy."),
+        )
+        .arg(
+            Arg::with_name("keep")
+                .long("keep")
+                .value_name("EXPRESSIONS")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Writes only the elements matching one of the expressions like \"w/highway n/amenity=cafe,restaurant r/type=route\" and the elements that they are referring to."),
+        )
+        .arg(
+            Arg::with_name("drop")
+                .long("drop")
+                .value_name("EXPRESSIONS")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Drops the elements matching one of the expressions unless a written element is referring to them."),
+        )
+        .arg(
+            Arg::with_name("keep-tags")
+                .long("keep-tags")
+                .value_name("KEYS")
+                .takes_value(true)
+                .help("Comma separated tag keys that are written. Other keys are left out. A * in a key is matching any characters."),
+        )
+        .arg(
+            Arg::with_name("drop-tags")
+                .long("drop-tags")
+                .value_name("KEYS")
+                .takes_value(true)
+                .help("Comma separated tag keys like \"tiger:*,source*\" that are left out. A * in a key is matching any characters."),
+        )
+        .arg(
+            Arg::with_name("types")
+                .long("types")
+                .value_name("TYPES")
+                .takes_value(true)
+                .use_delimiter(true)
+                .possible_values(&ElementType::NAMES)
+                .help("Comma separated element types that are written. The tables of other types are not written. All types are written by default."),
+        )
+        .arg(
+            Arg::with_name("no-metadata")
+                .long("no-metadata")
+                .help("Leaves out the version, changeset, user, uid, visible and timestamp columns of the nodes, ways and relations."),
+        )
+        .arg(
+            Arg::with_name("geometry")
+                .long("geometry")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(&GeometryFormat::NAMES)
+                .help("Writes the geometries of the ways and the multipolygon and boundary relations to a geom column as well-known text or hex encoded well-known binary. Closed ways with area tags are polygons and other ways are line strings. The input is read twice when relations are written."),
+        )
+        .arg(
+            Arg::with_name("node-index")
+                .long("node-index")
+                .value_name("INDEX")
+                .takes_value(true)
+                .possible_values(&IndexType::NAMES)
+                .default_value("memory")
+                .help("Storage of the node locations for the geometries. The disk index is a sparse temporary file for large inputs."),
+        )
+        .arg(
+            Arg::with_name("area-tags")
+                .long("area-tags")
+                .value_name("EXPRESSIONS")
+                .takes_value(true)
+                .help("Tag expressions like \"building landuse natural!=coastline\" of the closed ways that are polygons. Replaces the default expressions."),
+        )
+        .arg(
+            Arg::with_name("dialect")
+                .long("dialect")
+                .value_name("DIALECT")
+                .takes_value(true)
+                .possible_values(&Dialect::NAMES)
+                .default_value("mysql")
+                .help("SQL dialect of the output files."),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(&Format::NAMES)
+                .default_value("sql")
+                .help("Format of the output files. The copy format is writing PostgreSQL COPY files and a load.sql script. The csv and tsv formats are writing a header line and the rows of each table."),
+        )
         .get_matches();
 
+    if config.is_present("database") && config.occurrences_of("format") > 0 {
+        clap::Error::with_description(
+            "The format can not be used with a database.",
+            clap::ErrorKind::ArgumentConflict,
+        )
+        .exit();
+    }
+    let format = if config.is_present("database") {
+        Format::Database
+    } else {
+        Format::from_name(config.value_of("format").unwrap()).unwrap()
+    };
+    let dialect = Dialect::from_name(config.value_of("dialect").unwrap()).unwrap();
+    if format == Format::Copy
+        && config.occurrences_of("dialect") > 0
+        && dialect != Dialect::Postgres
+    {
+        clap::Error::with_description(
+            "The copy format is only available for the postgres dialect.",
+            clap::ErrorKind::ArgumentConflict,
+        )
+        .exit();
+    }
+    if format == Format::Database
+        && config.occurrences_of("dialect") > 0
+        && dialect != Dialect::Sqlite
+    {
+        clap::Error::with_description(
+            "A database can only be written in the sqlite dialect.",
+            clap::ErrorKind::ArgumentConflict,
+        )
+        .exit();
+    }
+    let tag_storage = TagStorage::from_name(config.value_of("tags").unwrap()).unwrap();
+    if tag_storage != TagStorage::Table && config.is_present("normalize-values") {
+        clap::Error::with_description(
+            "The tag values can only be normalized in the table storage.",
+            clap::ErrorKind::ArgumentConflict,
+        )
+        .exit();
+    }
+    let postgres = format == Format::Copy || dialect == Dialect::Postgres;
+    if tag_storage == TagStorage::Hstore && (format == Format::Database || !postgres) {
+        clap::Error::with_description(
+            "The hstore storage is only available for the postgres dialect.",
+            clap::ErrorKind::ArgumentConflict,
+        )
+        .exit();
+    }
+    let strategy = Strategy::from_name(config.value_of("strategy").unwrap()).unwrap();
+    if format != Format::Sql && config.value_of("output") == Some("-") {
+        clap::Error::with_description(
+            &format!(
+                "The {} format can not be written to the standard output.",
+                config.value_of("format").unwrap()
+            ),
+            clap::ErrorKind::ArgumentConflict,
+        )
+        .exit();
+    }
+
+    if (config.occurrences_of("node-index") > 0 || config.is_present("area-tags"))
+        && !config.is_present("geometry")
+    {
+        clap::Error::with_description(
+            "The node index and the area tags are only used with --geometry.",
+            clap::ErrorKind::ArgumentConflict,
+        )
+        .exit();
+    }
+
     let arguments = Arguments {
         input: String::from(config.value_of("input").unwrap()),
-        output: String::from(config.value_of("output").unwrap()),
+        output: String::from(
+            config
+                .value_of("output")
+                .or_else(|| config.value_of("database"))
+                .unwrap(),
+        ),
         maximum_rows: config.value_of("rows").unwrap().parse().unwrap(),
-        no_ignore: if let Some(_) = config.value_of("no-ignore") {
-            true
+        no_ignore: config.is_present("no-ignore"),
+        normalize_values: config.is_present("normalize-values"),
+        tag_storage,
+        tag_columns: TagColumn::parse_list(config.value_of("columns").unwrap_or(""))
+            .unwrap_or_else(|message| {
+                clap::Error::with_description(&message, clap::ErrorKind::InvalidValue).exit()
+            }),
+        row_group_size: config
+            .value_of("row-group-size")
+            .unwrap()
+            .parse()
+            .ok()
+            .filter(|size| *size > 0)
+            .unwrap_or_else(|| {
+                clap::Error::with_description(
+                    "The row group size should be a positive number.",
+                    clap::ErrorKind::InvalidValue,
+                )
+                .exit()
+            }),
+        region: config
+            .value_of("bbox")
+            .map(|bbox| Bbox::parse(bbox).map(Region::Bbox))
+            .or_else(|| {
+                config.value_of("polygon").map(|path| {
+                    Polygon::read(Path::new(path)).map(|polygon| Region::Polygon(Arc::new(polygon)))
+                })
+            })
+            .transpose()
+            .unwrap_or_else(|message| {
+                clap::Error::with_description(&message, clap::ErrorKind::InvalidValue).exit()
+            }),
+        strategy,
+        tag_filter: if config.is_present("keep") || config.is_present("drop") {
+            let values = |name| {
+                config
+                    .values_of(name)
+                    .map(|v| v.collect::<Vec<&str>>())
+                    .unwrap_or_default()
+            };
+            Some(
+                TagFilter::parse(&values("keep"), &values("drop")).unwrap_or_else(|message| {
+                    clap::Error::with_description(&message, clap::ErrorKind::InvalidValue).exit()
+                }),
+            )
         } else {
-            false
+            None
+        },
+        key_filter: if config.is_present("keep-tags") || config.is_present("drop-tags") {
+            Some(KeyFilter::parse(
+                config.value_of("keep-tags"),
+                config.value_of("drop-tags"),
+            ))
+        } else {
+            None
+        },
+        types: config
+            .values_of("types")
+            .map(|names| {
+                names
+                    .map(|name| ElementType::from_name(name).unwrap())
+                    .collect()
+            })
+            .unwrap_or_else(|| vec![ElementType::Node, ElementType::Way, ElementType::Relation]),
+        no_metadata: config.is_present("no-metadata"),
+        geometry: config
+            .value_of("geometry")
+            .map(|name| GeometryFormat::from_name(name).unwrap()),
+        node_index: IndexType::from_name(config.value_of("node-index").unwrap()).unwrap(),
+        area_tags: TagFilter::parse(
+            &[config.value_of("area-tags").unwrap_or(DEFAULT_AREA_TAGS)],
+            &[],
+        )
+        .unwrap_or_else(|message| {
+            clap::Error::with_description(&message, clap::ErrorKind::InvalidValue).exit()
+        }),
+        dialect: match format {
+            Format::Copy => Dialect::Postgres,
+            Format::Database => Dialect::Sqlite,
+            _ => dialect,
         },
+        format,
     };
 
-    let result = Reader::from_file(&Path::new(&arguments.input));
-    let (nodes_handle,nodes) = new_thread::<Node>(arguments.clone());
-    let (tags_handle,tags) = new_thread::<Tag>(arguments.clone());
-    let (ways_handle,ways) = new_thread::<Way>(arguments.clone());
-    let (way_nodes_handle,way_nodes) = new_thread::<WayNode>(arguments.clone());
-    let (relations_handle,relations) = new_thread::<Relation>(arguments.clone());
-    let (relation_members_handle, relation_members) = new_thread::<RelationMember>(arguments.clone());
-    let (ref_tags_handle, ref_tags) = new_thread::<UsedTag>(arguments.clone());
-
-    let mut used_tags: Vec<String> = vec![];
-    let mut last_ref_id: i64 = 0;
-    let mut last_ref_type: &str = "node";
-    let mut buf = vec![];
-
-    match result {
-        Ok(mut reader) => {
-            // Self closing tags
-            reader.expand_empty_elements(true);
-
-            loop {
-                match reader.read_event(&mut buf) {
-                    Ok(Event::Start(e)) => {
-                        let name = e.name();
-                        let attrs: HashMap<String, String> = e
-                            .attributes()
-                            .map(|a| {
-                                let attr = a.unwrap();
-                                let value = String::from_utf8(attr.value.to_vec()).unwrap();
-
-                                (str::from_utf8(attr.key).unwrap().to_string(), value)
-                            })
-                            .collect();
-
-                        match name {
-                            b"node" => {
-                                let mut node: Node = Node {
-                                    ..Default::default()
-                                };
-
-                                for (key, value) in attrs {
-                                    if !node.main_info.set_attribute(key.clone(), value.clone()) {
-                                        match key.as_str() {
-                                            "lat" => {
-                                                node.lat = value.parse::<f32>().unwrap();
-                                            }
-                                            "lon" => {
-                                                node.lng = value.parse::<f32>().unwrap();
-                                            }
-                                            _ => {}
-                                        }
-                                    }
-                                }
-
-                                last_ref_id = node.main_info.id;
-                                last_ref_type = "node";
-
-                                nodes.send(ThreadSignal::Write(node)).unwrap();
-                            }
-                            b"way" => {
-                                let mut way: Way = Way {
-                                    ..Default::default()
-                                };
-                                for (k, v) in attrs {
-                                    way.main_info.set_attribute(k, v);
-                                }
-
-                                last_ref_id = way.main_info.id;
-                                last_ref_type = "way";
-
-                                ways.send(ThreadSignal::Write(way)).unwrap();
-                            }
-                            b"relation" => {
-                                let mut relation: Relation = Relation {
-                                    ..Default::default()
-                                };
-                                for (k, v) in attrs {
-                                    relation.main_info.set_attribute(k, v);
-                                }
-
-                                last_ref_id = relation.main_info.id;
-                                last_ref_type = "relation";
-
-                                relations.send(ThreadSignal::Write(relation)).unwrap();
-                            }
-                            b"tag" => {
-                                let k = String::from(attrs.get("k").unwrap());
-                                let v = String::from(attrs.get("v").unwrap());
-
-                                let tag_index = used_tags.iter().position(|t| t == &k);
-
-                                let tag_id = match tag_index {
-                                    None => {
-                                        let id = used_tags.len() as i16;
-                                        let in_tag = Tag {
-                                            id,
-                                            name: k.clone(),
-                                        };
-
-                                        used_tags.push(k.clone());
-                                        tags.send(ThreadSignal::Write(in_tag)).unwrap();
-                                        id
-                                    }
-                                    Some(index) => index as i16,
-                                };
-
-                                ref_tags
-                                    .send(ThreadSignal::Write(UsedTag {
-                                        tag_id,
-                                        value: v,
-                                        ref_id: last_ref_id,
-                                        ref_type: String::from(last_ref_type),
-                                    }))
-                                    .unwrap();
-                            }
-                            b"nd" => {
-                                let ref_attr = attrs
-                                    .get("ref")
-                                    .expect("Can not read the ref attribute from nd tag.");
-
-                                way_nodes
-                                    .send(ThreadSignal::Write(WayNode {
-                                        way_id: last_ref_id,
-                                        node_id: ref_attr.parse::<i64>().unwrap(),
-                                    }))
-                                    .unwrap();
-                            }
-                            b"member" => {
-                                let ref_attr = attrs
-                                    .get("ref")
-                                    .expect("Can not read ref attr from member tag.");
-                                let type_attr = attrs
-                                    .get("type")
-                                    .expect("Can not read type attr from member tag.");
-                                let role_attr = attrs.get("role").unwrap();
-
-                                relation_members
-                                    .send(ThreadSignal::Write(RelationMember {
-                                        ref_id: ref_attr.parse::<i64>().unwrap(),
-                                        ref_type: type_attr.clone(),
-                                        role: role_attr.clone(),
-                                        relation_id: last_ref_id,
-                                    }))
-                                    .unwrap();
-                            }
-                            _ => (),
-                        }
-                    }
-                    Err(e) => panic!("Error at position {}: {:?}", reader.buffer_position(), e),
-                    Ok(Event::Eof) => break,
-                    _ => (),
-                }
-                buf.clear();
-            }
-        }
-        Err(e) => panic!("Invalid file :- {:?}", e),
+    if arguments.input == "-"
+        && (extract::is_multi_pass(&arguments) || multipolygon::is_enabled(&arguments))
+    {
+        clap::Error::with_description(
+            "The standard input can only be read once. Use the simple strategy without a tag filter and without relation geometries.",
+            clap::ErrorKind::ArgumentConflict,
+        )
+        .exit();
     }
 
-    nodes.send(ThreadSignal::Stop).unwrap();
-    tags.send(ThreadSignal::Stop).unwrap();
-    ways.send(ThreadSignal::Stop).unwrap();
-    way_nodes.send(ThreadSignal::Stop).unwrap();
-    relations.send(ThreadSignal::Stop).unwrap();
-    relation_members.send(ThreadSignal::Stop).unwrap();
-    ref_tags.send(ThreadSignal::Stop).unwrap();
-
-    nodes_handle.join().unwrap();
-    tags_handle.join().unwrap();
-    ways_handle.join().unwrap();
-    way_nodes_handle.join().unwrap();
-    relations_handle.join().unwrap();
-    relation_members_handle.join().unwrap();
-    ref_tags_handle.join().unwrap();
+    let mut converter = Converter::new(&arguments);
+    if arguments.region.is_some() || arguments.tag_filter.is_some() {
+        extract::read(&arguments, &mut converter);
+    } else {
+        input::read(Path::new(&arguments.input), &mut converter);
+    }
+    converter.finish();
 }
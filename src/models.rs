@@ -1,6 +1,9 @@
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::Arguments;
 
-#[derive(Default)]
 pub struct MainInfo {
     pub changeset: i32,
     pub id: i64,
@@ -9,10 +12,36 @@ pub struct MainInfo {
     pub user: String,
     pub uid: i32,
     pub visible: bool,
+    /// Tags of the element in the order of the input.
+    pub tags: Vec<(String, String)>,
+    /// Tags encoded for the tags column when the tags are not written to
+    /// the tags and ref_tags tables.
+    pub encoded_tags: Option<String>,
+    /// Tags that are written to their own columns.
+    pub tag_columns: Vec<(String, ColumnValue)>,
+}
+
+/// Elements are visible unless the input says otherwise. Only history
+/// files have deleted elements.
+impl Default for MainInfo {
+    fn default() -> MainInfo {
+        MainInfo {
+            changeset: 0,
+            id: 0,
+            version: 0,
+            timestamp: String::new(),
+            user: String::new(),
+            uid: 0,
+            visible: true,
+            tags: vec![],
+            encoded_tags: None,
+            tag_columns: vec![],
+        }
+    }
 }
 
 impl MainInfo {
-    fn get_data_set(&self) -> HashMap<&str, SqlType> {
+    fn get_data_set(&self) -> HashMap<&str, SqlType<'_>> {
         let mut data_set: HashMap<&str, SqlType> = HashMap::new();
         data_set.insert("changeset", SqlType::Int(self.changeset));
         data_set.insert("id", SqlType::BigInt(self.id));
@@ -21,10 +50,47 @@ impl MainInfo {
         data_set.insert("user", SqlType::Varchar(&self.user));
         data_set.insert("uid", SqlType::Int(self.uid));
         data_set.insert("visible", SqlType::Bool(self.visible));
+        if let Some(encoded_tags) = &self.encoded_tags {
+            data_set.insert("tags", SqlType::Varchar(encoded_tags));
+        }
+        for (key, value) in self.tag_columns.iter() {
+            data_set.insert(
+                key,
+                match value {
+                    ColumnValue::Text(text) => SqlType::Varchar(text),
+                    ColumnValue::Integer(integer) => SqlType::Int(*integer),
+                },
+            );
+        }
 
         data_set
     }
 
+    /// Columns of the main information without the id. The metadata
+    /// columns are left out with `--no-metadata`.
+    fn get_columns(arguments: &Arguments) -> Vec<Column> {
+        let mut columns = if arguments.no_metadata {
+            vec![]
+        } else {
+            vec![
+                Column::new("version", ColumnType::Int),
+                Column::new("changeset", ColumnType::Int),
+                Column::new("user", ColumnType::Varchar),
+                Column::new("uid", ColumnType::Int),
+                Column::new("visible", ColumnType::Bool),
+                Column::new("timestamp", ColumnType::Timestamp),
+            ]
+        };
+        if let Some(column_type) = arguments.tag_storage.column_type() {
+            columns.push(Column::new("tags", column_type));
+        }
+        for tag_column in arguments.tag_columns.iter() {
+            columns.push(Column::tag(&tag_column.key, tag_column.column_type));
+        }
+
+        columns
+    }
+
     pub fn set_attribute(&mut self, name: String, value: String) -> bool {
         match name.as_str() {
             "id" => {
@@ -40,11 +106,11 @@ impl MainInfo {
                 true
             }
             "timestamp" => {
-                self.timestamp = String::from(value);
+                self.timestamp = value;
                 true
             }
             "user" => {
-                self.user = String::from(value);
+                self.user = value;
                 true
             }
             "uid" => {
@@ -52,7 +118,7 @@ impl MainInfo {
                 true
             }
             "visible" => {
-                self.visible = if value == "true" { true } else { false };
+                self.visible = value == "true";
                 true
             }
             _ => false,
@@ -62,14 +128,14 @@ impl MainInfo {
 
 #[derive(Default)]
 pub struct Tag {
-    pub id: i16,
+    pub id: i32,
     pub name: String,
 }
 
 impl Model for Tag {
-    fn get_data_set<'a>(&'a self) -> HashMap<&'a str, SqlType> {
+    fn get_data_set(&self) -> HashMap<&str, SqlType<'_>> {
         let mut hash_map: HashMap<&str, SqlType> = HashMap::new();
-        hash_map.insert("id", SqlType::Int(self.id as i32));
+        hash_map.insert("id", SqlType::Int(self.id));
         hash_map.insert("name", SqlType::Varchar(self.name.as_str()));
 
         hash_map
@@ -79,31 +145,68 @@ impl Model for Tag {
         "tags"
     }
 
-    fn get_create_table_query() -> &'static str {
-        "CREATE TABLE tags(\
-            id INTEGER,\
-            name VARCHAR(256),
-            CONSTRAINT tags_pk PRIMARY KEY(id)\
-        )"
+    fn get_table(_arguments: &Arguments) -> Table {
+        Table {
+            name: Self::get_table_name(),
+            columns: vec![
+                Column::new("id", ColumnType::Int),
+                Column::new("name", ColumnType::Varchar),
+            ],
+            primary_key: vec!["id"],
+            foreign_keys: vec![],
+            indexes: vec![],
+        }
     }
+}
 
-    fn get_columns() -> Vec<&'static str> {
-        vec!["id", "name"]
+#[derive(Default)]
+pub struct TagValue {
+    pub id: i32,
+    pub value: String,
+}
+
+impl Model for TagValue {
+    fn get_data_set(&self) -> HashMap<&str, SqlType<'_>> {
+        let mut hash_map: HashMap<&str, SqlType> = HashMap::new();
+        hash_map.insert("id", SqlType::Int(self.id));
+        hash_map.insert("value", SqlType::Varchar(self.value.as_str()));
+
+        hash_map
+    }
+
+    fn get_table_name() -> &'static str {
+        "tag_values"
+    }
+
+    fn get_table(_arguments: &Arguments) -> Table {
+        Table {
+            name: Self::get_table_name(),
+            columns: vec![
+                Column::new("id", ColumnType::Int),
+                Column::new("value", ColumnType::Varchar),
+            ],
+            primary_key: vec!["id"],
+            foreign_keys: vec![],
+            indexes: vec![],
+        }
     }
 }
 
 #[derive(Default)]
 pub struct UsedTag {
-    pub tag_id: i16,
+    pub tag_id: i32,
     pub value: String,
+    /// Id of the value in the `tag_values` table when the values are
+    /// normalized. The value itself is not written then.
+    pub value_id: Option<i32>,
     pub ref_id: i64,
     pub ref_type: String,
 }
 
 impl Model for UsedTag {
-    fn get_data_set<'a>(&'a self) -> HashMap<&str, SqlType<'a>> {
+    fn get_data_set(&self) -> HashMap<&str, SqlType<'_>> {
         let mut hash_map: HashMap<&str, SqlType> = HashMap::new();
-        hash_map.insert("tag_id", SqlType::Int(self.tag_id as i32));
+        hash_map.insert("tag_id", SqlType::Int(self.tag_id));
         hash_map.insert(
             match self.ref_type.as_str() {
                 "relation" => "relation_id",
@@ -113,6 +216,10 @@ impl Model for UsedTag {
             },
             SqlType::BigInt(self.ref_id),
         );
+        match self.value_id {
+            Some(value_id) => hash_map.insert("value_id", SqlType::Int(value_id)),
+            None => hash_map.insert("value", SqlType::Varchar(&self.value)),
+        };
         hash_map
     }
 
@@ -120,45 +227,138 @@ impl Model for UsedTag {
         "ref_tags"
     }
 
-    fn get_create_table_query() -> &'static str {
-        "CREATE TABLE ref_tags (\
-            rt_id BIGINT AUTO_INCREMENT,\
-            tag_id INTEGER,\
-            node_id BIGINT DEFAULT NULL,\
-            relation_id BIGINT DEFAULT NULL,\
-            way_id BIGINT DEFAULT NULL, \
-            value VARCHAR(256),
-            CONSTRAINT ref_tags_pk PRIMARY KEY(rt_id),\
-            CONSTRAINT  ref_tags_tags_fk FOREIGN KEY(tag_id) REFERENCES tags(id),\
-            CONSTRAINT  ref_tags_nodes_fk FOREIGN KEY(node_id) REFERENCES nodes(id),\
-            CONSTRAINT  ref_tags_relations_fk FOREIGN KEY(relation_id) REFERENCES relations(id),\
-            CONSTRAINT  ref_tags_ways_fk FOREIGN KEY(way_id) REFERENCES ways(id)\
-        )"
+    fn get_table(arguments: &Arguments) -> Table {
+        let mut table = Table {
+            name: Self::get_table_name(),
+            columns: vec![
+                Column::new("rt_id", ColumnType::Serial),
+                Column::new("tag_id", ColumnType::Int),
+                Column::new("node_id", ColumnType::BigInt),
+                Column::new("relation_id", ColumnType::BigInt),
+                Column::new("way_id", ColumnType::BigInt),
+            ],
+            primary_key: vec!["rt_id"],
+            foreign_keys: written_foreign_keys(
+                arguments,
+                vec![
+                    ForeignKey::new("ref_tags_tags_fk", "tag_id", "tags"),
+                    ForeignKey::new("ref_tags_nodes_fk", "node_id", "nodes"),
+                    ForeignKey::new("ref_tags_relations_fk", "relation_id", "relations"),
+                    ForeignKey::new("ref_tags_ways_fk", "way_id", "ways"),
+                ],
+            ),
+            indexes: vec![],
+        };
+
+        if arguments.normalize_values {
+            table.columns.push(Column::new("value_id", ColumnType::Int));
+            table.foreign_keys.push(ForeignKey::new(
+                "ref_tags_tag_values_fk",
+                "value_id",
+                "tag_values",
+            ));
+        } else {
+            table
+                .columns
+                .push(Column::new("value", ColumnType::Varchar));
+        }
+
+        table
     }
+}
+
+/// Fixed-point coordinate in units of 1e-7 degrees. OSM is storing
+/// coordinates with this precision.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Coordinate(pub i32);
+
+impl Coordinate {
+    const SCALE: i64 = 10_000_000;
+
+    /// Parses a decimal number of degrees like `-12.2482632`. Digits after
+    /// the seventh decimal are rounded.
+    pub fn parse(value: &str) -> Option<Coordinate> {
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, value),
+        };
+        let (integer, fraction) = match digits.find('.') {
+            Some(dot) => (&digits[..dot], &digits[dot + 1..]),
+            None => (digits, ""),
+        };
+        if integer.is_empty() && fraction.is_empty()
+            || !integer
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
 
-    fn get_columns() -> Vec<&'static str> {
-        vec![
-            "tag_id",
-            "node_id",
-            "relation_id",
-            "way_id",
-            "value",
-        ]
+        let mut units = if integer.is_empty() {
+            0
+        } else {
+            integer.parse::<i64>().ok()?.checked_mul(Self::SCALE)?
+        };
+        let mut scale = Self::SCALE;
+        for (i, digit) in fraction.bytes().enumerate() {
+            let digit = i64::from(digit - b'0');
+            if i < 7 {
+                scale /= 10;
+                units += digit * scale;
+            } else {
+                if digit >= 5 {
+                    units += 1;
+                }
+                break;
+            }
+        }
+
+        let units = if negative { -units } else { units };
+        i32::try_from(units).ok().map(Coordinate)
+    }
+
+    /// Coordinate from nanodegrees. Rounded to the nearest 1e-7 degrees.
+    pub fn from_nanodegrees(nanodegrees: i64) -> Coordinate {
+        let units = (nanodegrees + 50 * nanodegrees.signum()) / 100;
+        Coordinate(units as i32)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        f64::from(self.0) / Self::SCALE as f64
+    }
+}
+
+/// Writes the coordinate with at most seven decimals and without trailing
+/// zeros, so a coordinate from an OSM file is written as it is in the file.
+impl fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let units = i64::from(self.0);
+        let sign = if units < 0 { "-" } else { "" };
+        let integer = units.abs() / Self::SCALE;
+        let fraction = units.abs() % Self::SCALE;
+
+        if fraction == 0 {
+            write!(f, "{}{}", sign, integer)
+        } else {
+            let fraction = format!("{:07}", fraction);
+            write!(f, "{}{}.{}", sign, integer, fraction.trim_end_matches('0'))
+        }
     }
 }
 
 #[derive(Default)]
 pub struct Node {
     pub main_info: MainInfo,
-    pub lat: f32,
-    pub lng: f32,
+    pub lat: Coordinate,
+    pub lng: Coordinate,
 }
 
 impl Model for Node {
-    fn get_data_set<'a>(&'a self) -> HashMap<&str, SqlType<'a>> {
+    fn get_data_set(&self) -> HashMap<&str, SqlType<'_>> {
         let mut data_set = self.main_info.get_data_set();
         data_set.insert("lat", SqlType::Decimal(self.lat));
-        data_set.insert("lng", SqlType::Decimal(self.lat));
+        data_set.insert("lng", SqlType::Decimal(self.lng));
         data_set
     }
 
@@ -166,73 +366,60 @@ impl Model for Node {
         "nodes"
     }
 
-    fn get_create_table_query() -> &'static str {
-        "CREATE TABLE nodes (\
-             id BIGINT,\
-             lat DECIMAL(10,8),\
-             lng DECIMAL(10,8),\
-             version INTEGER,\
-             changeset INTEGER,\
-             user VARCHAR(256),\
-             uid INTEGER,\
-             visible TINYINT(2),\
-             timestamp VARCHAR(256),\
-             CONSTRAINT nodes_pk PRIMARY KEY(id)\
-        )"
-    }
-
-    fn get_columns() -> Vec<&'static str> {
-        vec![
-            "id",
-            "lat",
-            "lng",
-            "version",
-            "changeset",
-            "user",
-            "uid",
-            "visible",
-            "timestamp",
-        ]
+    fn get_table(arguments: &Arguments) -> Table {
+        let mut columns = vec![
+            Column::new("id", ColumnType::BigInt),
+            Column::new("lat", ColumnType::Decimal),
+            Column::new("lng", ColumnType::Decimal),
+        ];
+        columns.append(&mut MainInfo::get_columns(arguments));
+
+        Table {
+            name: Self::get_table_name(),
+            columns,
+            primary_key: vec!["id"],
+            foreign_keys: vec![],
+            indexes: vec![],
+        }
     }
 }
 
 #[derive(Default)]
 pub struct Relation {
     pub main_info: MainInfo,
+    pub members: Vec<RelationMember>,
+    /// Encoded multipolygon of an area relation when the geometries are
+    /// written.
+    pub geom: Option<String>,
 }
 
 impl Model for Relation {
-    fn get_data_set<'a>(&'a self) -> HashMap<&str, SqlType<'a>> {
-        self.main_info.get_data_set()
+    fn get_data_set(&self) -> HashMap<&str, SqlType<'_>> {
+        let mut data_set = self.main_info.get_data_set();
+        if let Some(geom) = &self.geom {
+            data_set.insert("geom", SqlType::Varchar(geom));
+        }
+        data_set
     }
 
     fn get_table_name() -> &'static str {
         "relations"
     }
 
-    fn get_create_table_query() -> &'static str {
-        "CREATE TABLE relations (\
-             id BIGINT,\
-             version INTEGER,\
-             changeset INTEGER,\
-             user VARCHAR(256),\
-             uid INTEGER,\
-             visible TINYINT(2),\
-             timestamp VARCHAR(256),\
-             CONSTRAINT relations_pk PRIMARY KEY(id)\
-             )"
-    }
+    fn get_table(arguments: &Arguments) -> Table {
+        let mut columns = vec![Column::new("id", ColumnType::BigInt)];
+        columns.append(&mut MainInfo::get_columns(arguments));
+        if arguments.geometry.is_some() {
+            columns.push(Column::new("geom", ColumnType::Geometry));
+        }
 
-    fn get_columns() -> Vec<&'static str> {
-        vec![
-            "id",
-            "version",
-            "changeset",
-            "user",
-            "uid",
-            "visible",
-            "timestamp",
-        ]
+        Table {
+            name: Self::get_table_name(),
+            columns,
+            primary_key: vec!["id"],
+            foreign_keys: vec![],
+            indexes: vec![],
+        }
     }
 }
 
@@ -242,14 +429,17 @@ pub struct RelationMember {
     pub ref_id: i64,
     pub relation_id: i64,
     pub role: String,
+    /// Position of the member in the relation starting from zero.
+    pub sequence_id: i32,
 }
 
 impl Model for RelationMember {
-    fn get_data_set<'a>(&'a self) -> HashMap<&str, SqlType<'a>> {
+    fn get_data_set(&self) -> HashMap<&str, SqlType<'_>> {
         let mut hash_map: HashMap<&str, SqlType> = HashMap::new();
 
         hash_map.insert("relation_id", SqlType::BigInt(self.relation_id));
         hash_map.insert("role", SqlType::Varchar(self.role.as_str()));
+        hash_map.insert("sequence_id", SqlType::Int(self.sequence_id));
         hash_map.insert(
             match self.ref_type.as_str() {
                 "node" => "node_id",
@@ -267,70 +457,72 @@ impl Model for RelationMember {
         "relation_members"
     }
 
-    fn get_create_table_query() -> &'static str {
-        "CREATE TABLE relation_members (\
-            rm_id BIGINT AUTO_INCREMENT,\
-            relation_id BIGINT,\
-            node_id BIGINT DEFAULT NULL,\
-            way_id BIGINT DEFAULT NULL,\
-            sub_relation_id BIGINT DEFAULT NULL,\
-            role VARCHAR (256),\
-            CONSTRAINT relation_members_pk PRIMARY KEY(rm_id),\
-            CONSTRAINT  relation_members_nodes_fk FOREIGN KEY(node_id) REFERENCES nodes(id),\
-            CONSTRAINT  relation_members_ways_fk FOREIGN KEY(way_id) REFERENCES ways(id),\
-            CONSTRAINT  relation_members_relations_fk FOREIGN KEY(relation_id) REFERENCES relations(id),\
-            CONSTRAINT  relation_members_sub_relations_fk FOREIGN KEY(sub_relation_id) REFERENCES relations(id)\
-        )"
-    }
-
-    fn get_columns() -> Vec<&'static str> {
-        vec![
-            "relation_id",
-            "node_id",
-            "way_id",
-            "sub_relation_id",
-            "role",
-        ]
+    fn get_table(arguments: &Arguments) -> Table {
+        Table {
+            name: Self::get_table_name(),
+            columns: vec![
+                Column::new("relation_id", ColumnType::BigInt),
+                Column::new("node_id", ColumnType::BigInt),
+                Column::new("way_id", ColumnType::BigInt),
+                Column::new("sub_relation_id", ColumnType::BigInt),
+                Column::new("role", ColumnType::Varchar),
+                Column::new("sequence_id", ColumnType::Int),
+            ],
+            primary_key: vec!["relation_id", "sequence_id"],
+            foreign_keys: written_foreign_keys(
+                arguments,
+                vec![
+                    ForeignKey::new("relation_members_nodes_fk", "node_id", "nodes"),
+                    ForeignKey::new("relation_members_ways_fk", "way_id", "ways"),
+                    ForeignKey::new("relation_members_relations_fk", "relation_id", "relations"),
+                    ForeignKey::new(
+                        "relation_members_sub_relations_fk",
+                        "sub_relation_id",
+                        "relations",
+                    ),
+                ],
+            ),
+            indexes: vec![],
+        }
     }
 }
 
 #[derive(Default)]
 pub struct Way {
     pub main_info: MainInfo,
+    /// Ids of the nodes in the order of the way.
+    pub nodes: Vec<i64>,
+    /// Encoded geometry when the geometries are written.
+    pub geom: Option<String>,
 }
 
 impl Model for Way {
-    fn get_data_set<'a>(&'a self) -> HashMap<&str, SqlType<'a>> {
-        self.main_info.get_data_set()
+    fn get_data_set(&self) -> HashMap<&str, SqlType<'_>> {
+        let mut data_set = self.main_info.get_data_set();
+        if let Some(geom) = &self.geom {
+            data_set.insert("geom", SqlType::Varchar(geom));
+        }
+        data_set
     }
 
     fn get_table_name() -> &'static str {
         "ways"
     }
 
-    fn get_create_table_query() -> &'static str {
-        "CREATE TABLE ways (\
-             id BIGINT,\
-             version INTEGER,\
-             changeset INTEGER,\
-             user VARCHAR(256),\
-             uid INTEGER,\
-             visible TINYINT(2),\
-             timestamp VARCHAR(256),\
-             CONSTRAINT ways_pk PRIMARY KEY(id)\
-        )"
-    }
+    fn get_table(arguments: &Arguments) -> Table {
+        let mut columns = vec![Column::new("id", ColumnType::BigInt)];
+        columns.append(&mut MainInfo::get_columns(arguments));
+        if arguments.geometry.is_some() {
+            columns.push(Column::new("geom", ColumnType::Geometry));
+        }
 
-    fn get_columns() -> Vec<&'static str> {
-        vec![
-            "id",
-            "version",
-            "changeset",
-            "user",
-            "uid",
-            "visible",
-            "timestamp",
-        ]
+        Table {
+            name: Self::get_table_name(),
+            columns,
+            primary_key: vec!["id"],
+            foreign_keys: vec![],
+            indexes: vec![],
+        }
     }
 }
 
@@ -338,14 +530,17 @@ impl Model for Way {
 pub struct WayNode {
     pub way_id: i64,
     pub node_id: i64,
+    /// Position of the node in the way starting from zero.
+    pub sequence_id: i32,
 }
 
 impl Model for WayNode {
-    fn get_data_set<'a>(&'a self) -> HashMap<&str, SqlType<'a>> {
+    fn get_data_set(&self) -> HashMap<&str, SqlType<'_>> {
         let mut hash_map: HashMap<&str, SqlType> = HashMap::new();
 
         hash_map.insert("way_id", SqlType::BigInt(self.way_id));
         hash_map.insert("node_id", SqlType::BigInt(self.node_id));
+        hash_map.insert("sequence_id", SqlType::Int(self.sequence_id));
 
         hash_map
     }
@@ -354,37 +549,196 @@ impl Model for WayNode {
         "way_nodes"
     }
 
-    fn get_create_table_query() -> &'static str {
-        "CREATE TABLE way_nodes (\
-            way_id BIGINT,\
-            node_id BIGINT,\
-            CONSTRAINT way_nodes_pk PRIMARY KEY(way_id,node_id),\
-            CONSTRAINT  way_nodes_nodes_fk FOREIGN KEY(node_id) REFERENCES nodes(id),\
-            CONSTRAINT  way_nodes_ways_fk FOREIGN KEY(way_id) REFERENCES ways(id)\
-        )"
+    fn get_table(arguments: &Arguments) -> Table {
+        Table {
+            name: Self::get_table_name(),
+            columns: vec![
+                Column::new("way_id", ColumnType::BigInt),
+                Column::new("node_id", ColumnType::BigInt),
+                Column::new("sequence_id", ColumnType::Int),
+            ],
+            primary_key: vec!["way_id", "sequence_id"],
+            foreign_keys: written_foreign_keys(
+                arguments,
+                vec![
+                    ForeignKey::new("way_nodes_nodes_fk", "node_id", "nodes"),
+                    ForeignKey::new("way_nodes_ways_fk", "way_id", "ways"),
+                ],
+            ),
+            indexes: vec![Index::new("way_nodes_node_id_idx", vec!["node_id"])],
+        }
     }
+}
+
+/// Type of the OSM elements. Every type is written to its own tables.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ElementType {
+    /// The nodes table.
+    Node,
+    /// The ways and way_nodes tables.
+    Way,
+    /// The relations and relation_members tables.
+    Relation,
+}
 
-    fn get_columns() -> Vec<&'static str> {
-        vec!["way_id", "node_id"]
+impl ElementType {
+    /// Names of the types. Same as the names of their main tables.
+    pub const NAMES: [&'static str; 3] = ["nodes", "ways", "relations"];
+
+    pub fn from_name(name: &str) -> Option<ElementType> {
+        match name {
+            "nodes" => Some(ElementType::Node),
+            "ways" => Some(ElementType::Way),
+            "relations" => Some(ElementType::Relation),
+            _ => None,
+        }
     }
 }
 
-#[derive(Debug,Copy,Clone)]
+/// Leaves out the foreign keys referring to the tables of element types
+/// that are not written.
+fn written_foreign_keys(arguments: &Arguments, foreign_keys: Vec<ForeignKey>) -> Vec<ForeignKey> {
+    foreign_keys
+        .into_iter()
+        .filter(|foreign_key| {
+            ElementType::from_name(foreign_key.table)
+                .is_none_or(|element_type| arguments.types.contains(&element_type))
+        })
+        .collect()
+}
+
+/// Value of a tag in its own column.
+#[derive(Debug, Clone)]
+pub enum ColumnValue {
+    Text(String),
+    Integer(i32),
+}
+
+#[derive(Debug, Copy, Clone)]
 pub enum SqlType<'a> {
     BigInt(i64),
     Int(i32),
-    Decimal(f32),
+    Decimal(Coordinate),
     Varchar(&'a str),
     Bool(bool),
     Null,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ColumnType {
+    /// Auto incrementing big integer that is filled by the database.
+    Serial,
+    BigInt,
+    Int,
+    Decimal,
+    Varchar,
+    Bool,
+    /// ISO 8601 timestamp in UTC like `2008-09-21T21:37:45Z`.
+    Timestamp,
+    /// JSON object of the tags.
+    Json,
+    /// PostgreSQL hstore of the tags.
+    Hstore,
+    /// Well-known text or hex encoded well-known binary.
+    Geometry,
+}
+
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub name: String,
+    pub column_type: ColumnType,
+    /// Whether the name is always quoted. Tag keys can be any string, so
+    /// the columns of the tags may be keywords.
+    pub quoted: bool,
+}
+
+impl Column {
+    pub fn new(name: &str, column_type: ColumnType) -> Column {
+        Column {
+            name: String::from(name),
+            column_type,
+            quoted: false,
+        }
+    }
+
+    /// Column of a tag key.
+    pub fn tag(key: &str, column_type: ColumnType) -> Column {
+        Column {
+            quoted: true,
+            ..Column::new(key, column_type)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ForeignKey {
+    pub name: &'static str,
+    pub column: &'static str,
+    /// Referenced table. Always referencing the `id` column of the table.
+    pub table: &'static str,
+}
+
+impl ForeignKey {
+    pub fn new(name: &'static str, column: &'static str, table: &'static str) -> ForeignKey {
+        ForeignKey {
+            name,
+            column,
+            table,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Index {
+    pub name: &'static str,
+    pub columns: Vec<&'static str>,
+}
+
+impl Index {
+    pub fn new(name: &'static str, columns: Vec<&'static str>) -> Index {
+        Index { name, columns }
+    }
+}
+
+/// Dialect independent definition of a table.
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub name: &'static str,
+    pub columns: Vec<Column>,
+    pub primary_key: Vec<&'static str>,
+    pub foreign_keys: Vec<ForeignKey>,
+    /// Indexes that are created after inserting the rows.
+    pub indexes: Vec<Index>,
+}
+
+impl Table {
+    /// Columns that are filled by the data set. Serial columns are filled
+    /// by the database.
+    pub fn get_columns(&self) -> Vec<String> {
+        self.columns
+            .iter()
+            .filter(|column| column.column_type != ColumnType::Serial)
+            .map(|column| column.name.clone())
+            .collect()
+    }
+
+    /// Whether the primary key is a serial column filled by the database.
+    pub fn has_serial_key(&self) -> bool {
+        self.columns.iter().any(|column| {
+            column.column_type == ColumnType::Serial && self.primary_key == [column.name.as_str()]
+        })
+    }
+}
+
 pub trait Model {
-    fn get_data_set<'a>(&'a self) -> HashMap<&str, SqlType<'a>>;
+    fn get_data_set(&self) -> HashMap<&str, SqlType<'_>>;
 
     fn get_table_name() -> &'static str;
 
-    fn get_create_table_query() -> &'static str;
+    /// Definition of the table. Some tables depend on the options.
+    fn get_table(arguments: &Arguments) -> Table;
 
-    fn get_columns() -> Vec<&'static str>;
+    fn get_columns(arguments: &Arguments) -> Vec<String> {
+        Self::get_table(arguments).get_columns()
+    }
 }
@@ -0,0 +1,255 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::extract::is_area_relation;
+use crate::geometry::{Geometry, NodeLocations, Point};
+use crate::handler::Handler;
+use crate::input;
+use crate::models::{ElementType, Node, Relation, RelationMember, Way};
+use crate::poly::Ring;
+use crate::Arguments;
+
+/// Whether the geometries of the multipolygon and boundary relations are
+/// written. Their member ways are read in an additional pass.
+pub fn is_enabled(arguments: &Arguments) -> bool {
+    arguments.geometry.is_some() && arguments.types.contains(&ElementType::Relation)
+}
+
+/// Role of a member in the rings of an area. Ways without a role are
+/// outer ways. Other members are not a part of the area.
+fn ring_role(member: &RelationMember) -> Option<&'static str> {
+    if member.ref_type != "way" {
+        return None;
+    }
+    match member.role.as_str() {
+        "outer" | "" => Some("outer"),
+        "inner" => Some("inner"),
+        _ => None,
+    }
+}
+
+/// Collects the member ways of the area relations.
+struct MemberWays {
+    ways: HashSet<i64>,
+}
+
+impl Handler for MemberWays {
+    fn node(&mut self, _node: Node) {}
+
+    fn way(&mut self, _way: Way) {}
+
+    fn relation(&mut self, relation: Relation) {
+        if is_area_relation(&relation) {
+            self.ways.extend(
+                relation
+                    .members
+                    .iter()
+                    .filter(|member| ring_role(member).is_some())
+                    .map(|member| member.ref_id),
+            );
+        }
+    }
+}
+
+/// Closed ring joined from member ways.
+struct JoinedRing {
+    nodes: Vec<i64>,
+    /// Node ids of the ring for the containment tests.
+    node_set: HashSet<i64>,
+    points: Vec<Point>,
+    ring: Ring,
+    /// Twice the area. Positive when the ring is counterclockwise.
+    area: i128,
+}
+
+impl JoinedRing {
+    fn new(nodes: Vec<i64>, points: Vec<Point>) -> JoinedRing {
+        let coordinates: Vec<(i64, i64)> = points
+            .iter()
+            .map(|(lng, lat)| (i64::from(lng.0), i64::from(lat.0)))
+            .collect();
+        let area = coordinates
+            .windows(2)
+            .map(|pair| {
+                let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+                i128::from(x1) * i128::from(y2) - i128::from(x2) * i128::from(y1)
+            })
+            .sum();
+
+        JoinedRing {
+            node_set: nodes.iter().copied().collect(),
+            nodes,
+            points,
+            ring: Ring::new(coordinates),
+            area,
+        }
+    }
+
+    /// Whether the other ring is inside. Tested with the first node of the
+    /// other ring that is not shared with this ring.
+    fn contains(&self, other: &JoinedRing) -> bool {
+        other
+            .nodes
+            .iter()
+            .zip(other.points.iter())
+            .find(|(node_id, _)| !self.node_set.contains(node_id))
+            .is_some_and(|(_, (lng, lat))| self.ring.contains(i64::from(lng.0), i64::from(lat.0)))
+    }
+
+    /// Points of the ring in the counterclockwise or clockwise order.
+    fn oriented(mut self, counterclockwise: bool) -> Vec<Point> {
+        if (self.area > 0) != counterclockwise {
+            self.points.reverse();
+        }
+        self.points
+    }
+}
+
+/// Joins the ways end to end into closed rings. Rings are in the order of
+/// their first ways.
+fn join_rings(
+    ways: Vec<&Vec<i64>>,
+    role: &str,
+    locations: &mut NodeLocations,
+) -> Result<Vec<JoinedRing>, String> {
+    let mut segments: Vec<&[i64]> = ways
+        .into_iter()
+        .filter(|nodes| nodes.len() >= 2)
+        .map(|nodes| nodes.as_slice())
+        .collect();
+    let mut rings = vec![];
+
+    while !segments.is_empty() {
+        let mut nodes = segments.remove(0).to_vec();
+        while nodes.first() != nodes.last() {
+            let last = nodes[nodes.len() - 1];
+            let position = segments
+                .iter()
+                .position(|segment| segment[0] == last || segment[segment.len() - 1] == last)
+                .ok_or_else(|| format!("the {} ring is not closed at the node {}", role, last))?;
+            let segment = segments.remove(position);
+            if segment[0] == last {
+                nodes.extend_from_slice(&segment[1..]);
+            } else {
+                nodes.extend(segment.iter().rev().skip(1));
+            }
+        }
+
+        if nodes.len() < 4 {
+            return Err(format!(
+                "the {} ring at the node {} has less than four nodes",
+                role, nodes[0]
+            ));
+        }
+        let points = nodes
+            .iter()
+            .map(|node_id| {
+                locations
+                    .get(*node_id)
+                    .ok_or_else(|| format!("the node {} is not in the input", node_id))
+            })
+            .collect::<Result<Vec<Point>, String>>()?;
+        rings.push(JoinedRing::new(nodes, points));
+    }
+
+    Ok(rings)
+}
+
+/// Assembles the multipolygon and boundary relations from the nodes of
+/// their member ways.
+pub struct Assembler {
+    member_ways: HashSet<i64>,
+    /// Nodes of the member ways that are read.
+    ways: HashMap<i64, Vec<i64>>,
+}
+
+impl Assembler {
+    /// Reads the member ways of the area relations before the elements are
+    /// converted, because the relations are after the ways in the input.
+    pub fn read(path: &Path) -> Assembler {
+        let mut member_ways = MemberWays {
+            ways: HashSet::new(),
+        };
+        input::read(path, &mut member_ways);
+
+        Assembler {
+            member_ways: member_ways.ways,
+            ways: HashMap::new(),
+        }
+    }
+
+    pub fn way(&mut self, way: &Way) {
+        if self.member_ways.contains(&way.main_info.id) {
+            self.ways.insert(way.main_info.id, way.nodes.clone());
+        }
+    }
+
+    /// Builds the multipolygon of an area relation. Outer rings are
+    /// counterclockwise and every inner ring is a hole of the smallest outer
+    /// ring around it. A way listed more than once is used once with its
+    /// first role. Other relations have no geometry. The error is
+    /// describing why the relation is not a valid area.
+    pub fn relation(
+        &self,
+        relation: &Relation,
+        locations: &mut NodeLocations,
+    ) -> Result<Option<Geometry>, String> {
+        if !is_area_relation(relation) {
+            return Ok(None);
+        }
+
+        let mut outers = vec![];
+        let mut inners = vec![];
+        let mut member_ways = HashSet::new();
+        for member in relation.members.iter() {
+            let role = match ring_role(member) {
+                Some(role) if member_ways.insert(member.ref_id) => role,
+                _ => continue,
+            };
+            let nodes = self
+                .ways
+                .get(&member.ref_id)
+                .ok_or_else(|| format!("the member way {} is not in the input", member.ref_id))?;
+            if role == "outer" {
+                outers.push(nodes);
+            } else {
+                inners.push(nodes);
+            }
+        }
+        if outers.is_empty() {
+            return Err(String::from("it has no outer ways"));
+        }
+
+        let mut polygons: Vec<(JoinedRing, Vec<JoinedRing>)> =
+            join_rings(outers, "outer", locations)?
+                .into_iter()
+                .map(|outer| (outer, vec![]))
+                .collect();
+        for inner in join_rings(inners, "inner", locations)? {
+            let position = polygons
+                .iter()
+                .enumerate()
+                .filter(|(_, (outer, _))| outer.contains(&inner))
+                .min_by_key(|(_, (outer, _))| outer.area.abs())
+                .map(|(position, _)| position)
+                .ok_or_else(|| {
+                    format!(
+                        "the inner ring at the node {} is not inside of an outer ring",
+                        inner.nodes[0]
+                    )
+                })?;
+            polygons[position].1.push(inner);
+        }
+
+        Ok(Some(Geometry::MultiPolygon(
+            polygons
+                .into_iter()
+                .map(|(outer, inners)| {
+                    let mut rings = vec![outer.oriented(true)];
+                    rings.extend(inners.into_iter().map(|inner| inner.oriented(false)));
+                    rings
+                })
+                .collect(),
+        )))
+    }
+}
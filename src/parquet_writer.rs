@@ -0,0 +1,251 @@
+use parquet::basic::ZstdLevel;
+use parquet::basic::{Compression, LogicalType, Repetition, TimeUnit, Type as PhysicalType};
+use parquet::column::writer::ColumnWriter;
+use parquet::data_type::ByteArray;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::format::MilliSeconds;
+use parquet::schema::types::Type;
+use std::collections::HashMap;
+use std::io::prelude::*;
+use std::sync::Arc;
+
+use crate::models::*;
+use crate::writer::TableWriter;
+use crate::Arguments;
+
+/// Values of a column in the current row group. Null values are only
+/// recorded in the definition levels.
+enum Values {
+    Int64(Vec<i64>),
+    Int32(Vec<i32>),
+    Double(Vec<f64>),
+    Text(Vec<ByteArray>),
+    Boolean(Vec<bool>),
+}
+
+struct ColumnBuffer {
+    name: String,
+    column_type: ColumnType,
+    values: Values,
+    definition_levels: Vec<i16>,
+}
+
+/// Writes the rows of a table as an Apache Parquet file. All columns are
+/// optional because foreign key columns are null for other element types.
+pub struct ParquetWriter {
+    writer: Option<SerializedFileWriter<Box<dyn Write + Send>>>,
+    columns: Vec<ColumnBuffer>,
+    row_group_size: usize,
+    rows: usize,
+}
+
+impl ParquetWriter {
+    pub fn new(file: Box<dyn Write + Send>, table: Table, arguments: &Arguments) -> ParquetWriter {
+        let columns: Vec<ColumnBuffer> = table
+            .columns
+            .iter()
+            .filter(|column| column.column_type != ColumnType::Serial)
+            .map(|column| ColumnBuffer {
+                name: column.name.clone(),
+                column_type: column.column_type,
+                values: match column.column_type {
+                    ColumnType::Serial | ColumnType::BigInt | ColumnType::Timestamp => {
+                        Values::Int64(vec![])
+                    }
+                    ColumnType::Int => Values::Int32(vec![]),
+                    ColumnType::Decimal => Values::Double(vec![]),
+                    ColumnType::Varchar
+                    | ColumnType::Json
+                    | ColumnType::Hstore
+                    | ColumnType::Geometry => Values::Text(vec![]),
+                    ColumnType::Bool => Values::Boolean(vec![]),
+                },
+                definition_levels: vec![],
+            })
+            .collect();
+
+        let fields = table
+            .columns
+            .iter()
+            .filter(|column| column.column_type != ColumnType::Serial)
+            .map(|column| {
+                let (physical_type, logical_type) = match column.column_type {
+                    ColumnType::Serial | ColumnType::BigInt => (PhysicalType::INT64, None),
+                    ColumnType::Int => (PhysicalType::INT32, None),
+                    ColumnType::Decimal => (PhysicalType::DOUBLE, None),
+                    ColumnType::Varchar | ColumnType::Hstore | ColumnType::Geometry => {
+                        (PhysicalType::BYTE_ARRAY, Some(LogicalType::String))
+                    }
+                    ColumnType::Json => (PhysicalType::BYTE_ARRAY, Some(LogicalType::Json)),
+                    ColumnType::Bool => (PhysicalType::BOOLEAN, None),
+                    ColumnType::Timestamp => (
+                        PhysicalType::INT64,
+                        Some(LogicalType::Timestamp {
+                            is_adjusted_to_u_t_c: true,
+                            unit: TimeUnit::MILLIS(MilliSeconds {}),
+                        }),
+                    ),
+                };
+                Arc::new(
+                    Type::primitive_type_builder(&column.name, physical_type)
+                        .with_repetition(Repetition::OPTIONAL)
+                        .with_logical_type(logical_type)
+                        .build()
+                        .unwrap(),
+                )
+            })
+            .collect();
+        let schema = Type::group_type_builder(table.name)
+            .with_fields(fields)
+            .build()
+            .unwrap();
+
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .set_max_row_group_size(arguments.row_group_size)
+            .build();
+
+        let writer = SerializedFileWriter::new(file, Arc::new(schema), Arc::new(properties))
+            .unwrap_or_else(|e| panic!("Can not write the parquet file {}: {}", table.name, e));
+
+        ParquetWriter {
+            writer: Some(writer),
+            columns,
+            row_group_size: arguments.row_group_size,
+            rows: 0,
+        }
+    }
+
+    fn write_row_group(&mut self) {
+        let writer = self.writer.as_mut().unwrap();
+        let mut row_group = writer.next_row_group().unwrap();
+
+        for column in self.columns.iter_mut() {
+            let mut column_writer = row_group.next_column().unwrap().unwrap();
+            let levels = Some(&column.definition_levels[..]);
+            let result = match (column_writer.untyped(), &column.values) {
+                (ColumnWriter::Int64ColumnWriter(w), Values::Int64(v)) => {
+                    w.write_batch(v, levels, None)
+                }
+                (ColumnWriter::Int32ColumnWriter(w), Values::Int32(v)) => {
+                    w.write_batch(v, levels, None)
+                }
+                (ColumnWriter::DoubleColumnWriter(w), Values::Double(v)) => {
+                    w.write_batch(v, levels, None)
+                }
+                (ColumnWriter::ByteArrayColumnWriter(w), Values::Text(v)) => {
+                    w.write_batch(v, levels, None)
+                }
+                (ColumnWriter::BoolColumnWriter(w), Values::Boolean(v)) => {
+                    w.write_batch(v, levels, None)
+                }
+                _ => panic!("Unexpected parquet type of the column {}.", column.name),
+            };
+            result.unwrap();
+            column_writer.close().unwrap();
+
+            match &mut column.values {
+                Values::Int64(v) => v.clear(),
+                Values::Int32(v) => v.clear(),
+                Values::Double(v) => v.clear(),
+                Values::Text(v) => v.clear(),
+                Values::Boolean(v) => v.clear(),
+            }
+            column.definition_levels.clear();
+        }
+
+        row_group.close().unwrap();
+        self.rows = 0;
+    }
+}
+
+impl TableWriter for ParquetWriter {
+    fn write_row(&mut self, data_set: &HashMap<&str, SqlType>) {
+        for column in self.columns.iter_mut() {
+            let value = data_set.get(column.name.as_str()).unwrap_or(&SqlType::Null);
+            let defined = match (&mut column.values, value) {
+                (_, SqlType::Null) => false,
+                (Values::Int64(v), SqlType::Varchar(timestamp))
+                    if column.column_type == ColumnType::Timestamp =>
+                {
+                    match parse_timestamp(timestamp) {
+                        Some(milliseconds) => {
+                            v.push(milliseconds);
+                            true
+                        }
+                        None => false,
+                    }
+                }
+                (Values::Int64(v), SqlType::BigInt(big_int)) => {
+                    v.push(*big_int);
+                    true
+                }
+                (Values::Int32(v), SqlType::Int(int)) => {
+                    v.push(*int);
+                    true
+                }
+                (Values::Int64(v), SqlType::Int(int)) => {
+                    v.push(i64::from(*int));
+                    true
+                }
+                (Values::Double(v), SqlType::Decimal(coordinate)) => {
+                    v.push(coordinate.to_f64());
+                    true
+                }
+                (Values::Text(v), SqlType::Varchar(varchar)) => {
+                    v.push(ByteArray::from(*varchar));
+                    true
+                }
+                (Values::Boolean(v), SqlType::Bool(b)) => {
+                    v.push(*b);
+                    true
+                }
+                _ => panic!("Unexpected value of the column {}.", column.name),
+            };
+            column.definition_levels.push(if defined { 1 } else { 0 });
+        }
+
+        self.rows += 1;
+        if self.rows >= self.row_group_size {
+            self.write_row_group();
+        }
+    }
+
+    fn finish(&mut self) {
+        if self.rows > 0 {
+            self.write_row_group();
+        }
+        let mut file = self.writer.take().unwrap().into_inner().unwrap();
+        file.flush().unwrap();
+    }
+}
+
+/// Milliseconds since the unix epoch of a timestamp like
+/// `2008-09-21T21:37:45Z`. Empty and invalid timestamps are `None`.
+fn parse_timestamp(timestamp: &str) -> Option<i64> {
+    let bytes = timestamp.as_bytes();
+    if bytes.len() != 20
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || bytes[10] != b'T'
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+        || bytes[19] != b'Z'
+    {
+        return None;
+    }
+    let number = |start: usize, end: usize| timestamp.get(start..end)?.parse::<i64>().ok();
+    let (year, month, day) = (number(0, 4)?, number(5, 7)?, number(8, 10)?);
+    let (hour, minute, second) = (number(11, 13)?, number(14, 16)?, number(17, 19)?);
+
+    // Days since 1970-01-01 of the civil date. (Howard Hinnant's algorithm)
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    Some(((days * 86400) + hour * 3600 + minute * 60 + second) * 1000)
+}
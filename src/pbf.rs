@@ -0,0 +1,569 @@
+use flate2::read::ZlibDecoder;
+use std::io::{self, ErrorKind, Read};
+
+use crate::handler::Handler;
+use crate::models::*;
+
+/// Features that this decoder can read. Files requiring any other feature
+/// are rejected in the header block.
+const SUPPORTED_FEATURES: [&str; 3] = ["OsmSchema-V0.6", "DenseNodes", "HistoricalInformation"];
+
+/// Protocol buffer values that are used in the OSM PBF format.
+enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+/// Iterates over the fields of an encoded protocol buffer message.
+struct Message<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Message<'a> {
+    fn new(data: &'a [u8]) -> Message<'a> {
+        Message { data, pos: 0 }
+    }
+
+    fn varint(&mut self) -> io::Result<u64> {
+        read_varint(self.data, &mut self.pos)
+    }
+
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.data.len() - self.pos < len {
+            return Err(invalid("Unexpected end of a protocol buffer message."));
+        }
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+}
+
+impl<'a> Iterator for Message<'a> {
+    type Item = io::Result<(u32, Field<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.data.len() {
+            return None;
+        }
+
+        let field = self.varint().and_then(|key| {
+            let value = match key & 7 {
+                0 => Field::Varint(self.varint()?),
+                1 => {
+                    self.take(8)?;
+                    Field::Fixed
+                }
+                2 => {
+                    let len = self.varint()? as usize;
+                    Field::Bytes(self.take(len)?)
+                }
+                5 => {
+                    self.take(4)?;
+                    Field::Fixed
+                }
+                wire_type => {
+                    return Err(invalid(&format!(
+                        "Unsupported protocol buffer wire type {}.",
+                        wire_type
+                    )))
+                }
+            };
+            Ok(((key >> 3) as u32, value))
+        });
+
+        if field.is_err() {
+            // Do not try to read anything after a broken field.
+            self.pos = self.data.len();
+        }
+
+        Some(field)
+    }
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> io::Result<u64> {
+    let mut value: u64 = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = *data
+            .get(*pos)
+            .ok_or_else(|| invalid("Unexpected end of a varint."))?;
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid("Varint is too long."))
+}
+
+fn zigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+fn varint(field: Field) -> io::Result<u64> {
+    match field {
+        Field::Varint(value) => Ok(value),
+        _ => Err(invalid("Expected a varint field.")),
+    }
+}
+
+fn bytes(field: Field<'_>) -> io::Result<&[u8]> {
+    match field {
+        Field::Bytes(value) => Ok(value),
+        _ => Err(invalid("Expected a length delimited field.")),
+    }
+}
+
+/// Appends the values of a repeated varint field. Both the packed and the
+/// unpacked encodings are accepted.
+fn extend_varints(values: &mut Vec<u64>, field: Field) -> io::Result<()> {
+    match field {
+        Field::Varint(value) => values.push(value),
+        Field::Bytes(data) => {
+            let mut pos = 0;
+            while pos < data.len() {
+                values.push(read_varint(data, &mut pos)?);
+            }
+        }
+        Field::Fixed => return Err(invalid("Expected a repeated varint field.")),
+    }
+    Ok(())
+}
+
+/// Reverts the delta coding of a repeated sint64 field.
+fn delta_decode(values: &[u64]) -> Vec<i64> {
+    let mut last = 0;
+    values
+        .iter()
+        .map(|value| {
+            last += zigzag(*value);
+            last
+        })
+        .collect()
+}
+
+/// Formats seconds since the unix epoch as an OSM XML timestamp.
+fn format_timestamp(seconds: i64) -> String {
+    let days = seconds.div_euclid(86400);
+    let secs = seconds.rem_euclid(86400);
+
+    // Civil date from days since 1970-01-01. (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// Values of a primitive block that are shared between all of its groups.
+struct Block {
+    strings: Vec<String>,
+    granularity: i64,
+    lat_offset: i64,
+    lon_offset: i64,
+    date_granularity: i64,
+}
+
+impl Block {
+    fn string(&self, index: u64) -> io::Result<String> {
+        self.strings
+            .get(index as usize)
+            .cloned()
+            .ok_or_else(|| invalid("String table index is out of range."))
+    }
+
+    fn lat(&self, lat: i64) -> Coordinate {
+        Coordinate::from_nanodegrees(self.lat_offset + self.granularity * lat)
+    }
+
+    fn lng(&self, lon: i64) -> Coordinate {
+        Coordinate::from_nanodegrees(self.lon_offset + self.granularity * lon)
+    }
+
+    fn timestamp(&self, timestamp: i64) -> String {
+        format_timestamp(timestamp * self.date_granularity / 1000)
+    }
+
+    fn tags(&self, keys: &[u64], values: &[u64]) -> io::Result<Vec<(String, String)>> {
+        if keys.len() != values.len() {
+            return Err(invalid("Tag keys and values are not matching."));
+        }
+        keys.iter()
+            .zip(values)
+            .map(|(k, v)| Ok((self.string(*k)?, self.string(*v)?)))
+            .collect()
+    }
+
+    fn info(&self, id: i64, data: Option<&[u8]>) -> io::Result<MainInfo> {
+        let mut main_info = MainInfo {
+            id,
+            ..Default::default()
+        };
+
+        if let Some(data) = data {
+            for field in Message::new(data) {
+                let (number, field) = field?;
+                match number {
+                    1 => main_info.version = varint(field)? as i16,
+                    2 => main_info.timestamp = self.timestamp(varint(field)? as i64),
+                    3 => main_info.changeset = varint(field)? as i32,
+                    4 => main_info.uid = varint(field)? as i32,
+                    5 => main_info.user = self.string(varint(field)?)?,
+                    6 => main_info.visible = varint(field)? != 0,
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(main_info)
+    }
+}
+
+fn read_block(data: &[u8], handler: &mut dyn Handler) -> io::Result<()> {
+    let mut block = Block {
+        strings: vec![],
+        granularity: 100,
+        lat_offset: 0,
+        lon_offset: 0,
+        date_granularity: 1000,
+    };
+    let mut groups = vec![];
+
+    for field in Message::new(data) {
+        let (number, field) = field?;
+        match number {
+            1 => {
+                for string in Message::new(bytes(field)?) {
+                    let (number, string) = string?;
+                    if number == 1 {
+                        block
+                            .strings
+                            .push(String::from_utf8_lossy(bytes(string)?).into_owned());
+                    }
+                }
+            }
+            2 => groups.push(bytes(field)?),
+            17 => block.granularity = varint(field)? as i64,
+            18 => block.date_granularity = varint(field)? as i64,
+            19 => block.lat_offset = varint(field)? as i64,
+            20 => block.lon_offset = varint(field)? as i64,
+            _ => {}
+        }
+    }
+
+    for group in groups {
+        for field in Message::new(group) {
+            let (number, field) = field?;
+            match number {
+                1 => read_node(&block, bytes(field)?, handler)?,
+                2 => read_dense_nodes(&block, bytes(field)?, handler)?,
+                3 => read_way(&block, bytes(field)?, handler)?,
+                4 => read_relation(&block, bytes(field)?, handler)?,
+                _ => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn read_node(block: &Block, data: &[u8], handler: &mut dyn Handler) -> io::Result<()> {
+    let mut id = 0;
+    let mut info = None;
+    let mut keys = vec![];
+    let mut values = vec![];
+    let mut lat = 0;
+    let mut lon = 0;
+
+    for field in Message::new(data) {
+        let (number, field) = field?;
+        match number {
+            1 => id = zigzag(varint(field)?),
+            2 => extend_varints(&mut keys, field)?,
+            3 => extend_varints(&mut values, field)?,
+            4 => info = Some(bytes(field)?),
+            8 => lat = zigzag(varint(field)?),
+            9 => lon = zigzag(varint(field)?),
+            _ => {}
+        }
+    }
+
+    let mut main_info = block.info(id, info)?;
+    main_info.tags = block.tags(&keys, &values)?;
+    handler.node(Node {
+        main_info,
+        lat: block.lat(lat),
+        lng: block.lng(lon),
+    });
+    Ok(())
+}
+
+fn read_dense_nodes(block: &Block, data: &[u8], handler: &mut dyn Handler) -> io::Result<()> {
+    let mut ids = vec![];
+    let mut lats = vec![];
+    let mut lons = vec![];
+    let mut keys_vals = vec![];
+    let mut versions = vec![];
+    let mut timestamps = vec![];
+    let mut changesets = vec![];
+    let mut uids = vec![];
+    let mut user_sids = vec![];
+    let mut visibles = vec![];
+
+    for field in Message::new(data) {
+        let (number, field) = field?;
+        match number {
+            1 => extend_varints(&mut ids, field)?,
+            5 => {
+                for field in Message::new(bytes(field)?) {
+                    let (number, field) = field?;
+                    match number {
+                        1 => extend_varints(&mut versions, field)?,
+                        2 => extend_varints(&mut timestamps, field)?,
+                        3 => extend_varints(&mut changesets, field)?,
+                        4 => extend_varints(&mut uids, field)?,
+                        5 => extend_varints(&mut user_sids, field)?,
+                        6 => extend_varints(&mut visibles, field)?,
+                        _ => {}
+                    }
+                }
+            }
+            8 => extend_varints(&mut lats, field)?,
+            9 => extend_varints(&mut lons, field)?,
+            10 => extend_varints(&mut keys_vals, field)?,
+            _ => {}
+        }
+    }
+
+    if lats.len() != ids.len() || lons.len() != ids.len() {
+        return Err(invalid("Dense node coordinates are not matching the ids."));
+    }
+
+    let ids = delta_decode(&ids);
+    let lats = delta_decode(&lats);
+    let lons = delta_decode(&lons);
+    let timestamps = delta_decode(&timestamps);
+    let changesets = delta_decode(&changesets);
+    let uids = delta_decode(&uids);
+    let user_sids = delta_decode(&user_sids);
+    let has_info = !versions.is_empty();
+    let mut keys_vals = keys_vals.into_iter();
+
+    for (i, id) in ids.into_iter().enumerate() {
+        let mut main_info = block.info(id, None)?;
+        if has_info {
+            let missing = || invalid("Dense node metadata is not matching the ids.");
+            main_info.version = *versions.get(i).ok_or_else(missing)? as i16;
+            main_info.timestamp = block.timestamp(*timestamps.get(i).ok_or_else(missing)?);
+            main_info.changeset = *changesets.get(i).ok_or_else(missing)? as i32;
+            main_info.uid = *uids.get(i).ok_or_else(missing)? as i32;
+            main_info.user = block.string(*user_sids.get(i).ok_or_else(missing)? as u64)?;
+        }
+        if let Some(visible) = visibles.get(i) {
+            main_info.visible = *visible != 0;
+        }
+
+        // Tags of all nodes are stored as (key, value)* 0 sequences.
+        while let Some(k) = keys_vals.next() {
+            if k == 0 {
+                break;
+            }
+            let v = keys_vals
+                .next()
+                .ok_or_else(|| invalid("Dense node tag has no value."))?;
+            main_info.tags.push((block.string(k)?, block.string(v)?));
+        }
+
+        handler.node(Node {
+            main_info,
+            lat: block.lat(lats[i]),
+            lng: block.lng(lons[i]),
+        });
+    }
+
+    Ok(())
+}
+
+fn read_way(block: &Block, data: &[u8], handler: &mut dyn Handler) -> io::Result<()> {
+    let mut id = 0;
+    let mut info = None;
+    let mut keys = vec![];
+    let mut values = vec![];
+    let mut refs = vec![];
+
+    for field in Message::new(data) {
+        let (number, field) = field?;
+        match number {
+            1 => id = varint(field)? as i64,
+            2 => extend_varints(&mut keys, field)?,
+            3 => extend_varints(&mut values, field)?,
+            4 => info = Some(bytes(field)?),
+            8 => extend_varints(&mut refs, field)?,
+            _ => {}
+        }
+    }
+
+    let mut main_info = block.info(id, info)?;
+    main_info.tags = block.tags(&keys, &values)?;
+    handler.way(Way {
+        main_info,
+        nodes: delta_decode(&refs),
+        ..Default::default()
+    });
+    Ok(())
+}
+
+fn read_relation(block: &Block, data: &[u8], handler: &mut dyn Handler) -> io::Result<()> {
+    let mut id = 0;
+    let mut info = None;
+    let mut keys = vec![];
+    let mut values = vec![];
+    let mut roles = vec![];
+    let mut member_ids = vec![];
+    let mut types = vec![];
+
+    for field in Message::new(data) {
+        let (number, field) = field?;
+        match number {
+            1 => id = varint(field)? as i64,
+            2 => extend_varints(&mut keys, field)?,
+            3 => extend_varints(&mut values, field)?,
+            4 => info = Some(bytes(field)?),
+            8 => extend_varints(&mut roles, field)?,
+            9 => extend_varints(&mut member_ids, field)?,
+            10 => extend_varints(&mut types, field)?,
+            _ => {}
+        }
+    }
+
+    if roles.len() != member_ids.len() || types.len() != member_ids.len() {
+        return Err(invalid("Relation member fields are not matching."));
+    }
+
+    let mut relation = Relation {
+        main_info: block.info(id, info)?,
+        members: vec![],
+        geom: None,
+    };
+    relation.main_info.tags = block.tags(&keys, &values)?;
+    let members = roles.iter().zip(delta_decode(&member_ids)).zip(types);
+    for (sequence_id, ((role, ref_id), ref_type)) in members.enumerate() {
+        let ref_type = match ref_type {
+            0 => "node",
+            1 => "way",
+            2 => "relation",
+            _ => return Err(invalid("Unknown relation member type.")),
+        };
+        relation.members.push(RelationMember {
+            ref_type: String::from(ref_type),
+            ref_id,
+            role: block.string(*role)?,
+            relation_id: id,
+            sequence_id: sequence_id as i32,
+        });
+    }
+    handler.relation(relation);
+    Ok(())
+}
+
+fn read_header(data: &[u8]) -> io::Result<()> {
+    for field in Message::new(data) {
+        let (number, field) = field?;
+        if number == 4 {
+            let feature = String::from_utf8_lossy(bytes(field)?);
+            if !SUPPORTED_FEATURES.contains(&feature.as_ref()) {
+                return Err(invalid(&format!(
+                    "Required feature {} is not supported.",
+                    feature
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the uncompressed data of a file block.
+fn read_blob(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut raw_size = 0;
+    for field in Message::new(data) {
+        let (number, field) = field?;
+        match number {
+            1 => return Ok(bytes(field)?.to_vec()),
+            2 => raw_size = varint(field)? as usize,
+            3 => {
+                let mut uncompressed = Vec::with_capacity(raw_size);
+                ZlibDecoder::new(bytes(field)?).read_to_end(&mut uncompressed)?;
+                return Ok(uncompressed);
+            }
+            4..=7 => {
+                return Err(invalid(
+                    "Only raw and zlib compressed blocks are supported.",
+                ))
+            }
+            _ => {}
+        }
+    }
+    Err(invalid("File block has no data."))
+}
+
+/// Reads an OSM PBF file and passes every element to the handler.
+pub fn read<R: Read>(mut input: R, handler: &mut dyn Handler) -> io::Result<()> {
+    loop {
+        let mut size = [0; 4];
+        match input.read_exact(&mut size) {
+            Ok(()) => {}
+            Err(ref e) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e),
+        }
+
+        let mut header = vec![0; u32::from_be_bytes(size) as usize];
+        input.read_exact(&mut header)?;
+
+        let mut block_type = String::new();
+        let mut data_size = 0;
+        for field in Message::new(&header) {
+            let (number, field) = field?;
+            match number {
+                1 => block_type = String::from_utf8_lossy(bytes(field)?).into_owned(),
+                3 => data_size = varint(field)? as usize,
+                _ => {}
+            }
+        }
+
+        let mut blob = vec![0; data_size];
+        input.read_exact(&mut blob)?;
+
+        match block_type.as_str() {
+            "OSMHeader" => read_header(&read_blob(&blob)?)?,
+            "OSMData" => read_block(&read_blob(&blob)?, handler)?,
+            // Unknown blocks should be skipped.
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks whether the first bytes of a file are a PBF header block.
+pub fn is_pbf(start: &[u8]) -> bool {
+    start.len() >= 15 && start[4] == 0x0a && start[5] == 9 && &start[6..15] == b"OSMHeader"
+}
@@ -0,0 +1,164 @@
+use std::fs;
+use std::path::Path;
+
+use crate::models::{Coordinate, Node};
+
+/// Closed ring of a polygon. The last point is connected to the first one.
+#[derive(Debug)]
+pub struct Ring {
+    /// Longitude and latitude of the points in 1e-7 degrees.
+    points: Vec<(i64, i64)>,
+    /// Bounding box of the points as (min_lon, min_lat, max_lon, max_lat).
+    bounds: (i64, i64, i64, i64),
+}
+
+impl Ring {
+    pub fn new(points: Vec<(i64, i64)>) -> Ring {
+        let bounds = points.iter().fold(
+            (i64::MAX, i64::MAX, i64::MIN, i64::MIN),
+            |(min_lon, min_lat, max_lon, max_lat), (lon, lat)| {
+                (
+                    min_lon.min(*lon),
+                    min_lat.min(*lat),
+                    max_lon.max(*lon),
+                    max_lat.max(*lat),
+                )
+            },
+        );
+
+        Ring { points, bounds }
+    }
+
+    /// Even-odd test of a ray from the point to the east.
+    pub fn contains(&self, lon: i64, lat: i64) -> bool {
+        let (min_lon, min_lat, max_lon, max_lat) = self.bounds;
+        if lon < min_lon || lon > max_lon || lat < min_lat || lat > max_lat {
+            return false;
+        }
+
+        let mut inside = false;
+        let mut previous = self.points[self.points.len() - 1];
+        for point in self.points.iter() {
+            let (lon_i, lat_i) = *point;
+            let (lon_j, lat_j) = previous;
+            if (lat_i > lat) != (lat_j > lat) {
+                // Whether the point is west of the edge at its latitude.
+                // Compared without division to stay exact.
+                let left = i128::from(lon - lon_i) * i128::from(lat_j - lat_i);
+                let right = i128::from(lon_j - lon_i) * i128::from(lat - lat_i);
+                if (lat_j > lat_i && left < right) || (lat_j < lat_i && left > right) {
+                    inside = !inside;
+                }
+            }
+            previous = *point;
+        }
+
+        inside
+    }
+}
+
+/// Area of an Osmosis polygon file. A point is inside the area when it is
+/// inside of an outer ring and not inside of a hole.
+#[derive(Debug)]
+pub struct Polygon {
+    outers: Vec<Ring>,
+    holes: Vec<Ring>,
+}
+
+impl Polygon {
+    pub fn read(path: &Path) -> Result<Polygon, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Can not read the polygon file {:?}: {}", path, e))?;
+        Polygon::parse(&content)
+    }
+
+    /// Parses the polygon file format of Osmosis. The first line is the
+    /// name of the polygon. Every ring starts with a name line and ends with
+    /// an END line. Names of holes are starting with `!`. The file ends
+    /// with another END line.
+    pub fn parse(content: &str) -> Result<Polygon, String> {
+        let mut lines = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty());
+        lines
+            .next()
+            .ok_or_else(|| String::from("The polygon file is empty."))?;
+
+        let mut polygon = Polygon {
+            outers: vec![],
+            holes: vec![],
+        };
+
+        loop {
+            let name = lines
+                .next()
+                .ok_or_else(|| String::from("The polygon file has no END line."))?;
+            if name == "END" {
+                break;
+            }
+
+            let mut points = vec![];
+            loop {
+                let line = lines.next().ok_or_else(|| {
+                    format!("The ring {} of the polygon file has no END line.", name)
+                })?;
+                if line == "END" {
+                    break;
+                }
+                points.push(parse_point(line)?);
+            }
+
+            if points.len() < 3 {
+                return Err(format!(
+                    "The ring {} of the polygon file has less than three points.",
+                    name
+                ));
+            }
+            if name.starts_with('!') {
+                polygon.holes.push(Ring::new(points));
+            } else {
+                polygon.outers.push(Ring::new(points));
+            }
+        }
+
+        if polygon.outers.is_empty() {
+            return Err(String::from("The polygon file has no outer ring."));
+        }
+
+        Ok(polygon)
+    }
+
+    pub fn contains(&self, node: &Node) -> bool {
+        let lon = i64::from(node.lng.0);
+        let lat = i64::from(node.lat.0);
+
+        self.outers.iter().any(|ring| ring.contains(lon, lat))
+            && !self.holes.iter().any(|ring| ring.contains(lon, lat))
+    }
+}
+
+/// Parses a `lon lat` line. Coordinates can be written in the scientific
+/// notation like `1.234E+01`.
+fn parse_point(line: &str) -> Result<(i64, i64), String> {
+    let coordinates: Vec<i64> = line
+        .split_whitespace()
+        .map(|coordinate| {
+            Coordinate::parse(coordinate)
+                .or_else(|| {
+                    coordinate
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|degrees| degrees.abs() <= 180.0)
+                        .map(|degrees| Coordinate((degrees * 1e7).round() as i32))
+                })
+                .map(|coordinate| i64::from(coordinate.0))
+                .ok_or_else(|| format!("Invalid coordinate {} in the polygon file.", coordinate))
+        })
+        .collect::<Result<_, _>>()?;
+
+    match coordinates[..] {
+        [lon, lat] => Ok((lon, lat)),
+        _ => Err(format!("Invalid point {} in the polygon file.", line)),
+    }
+}
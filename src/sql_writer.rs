@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::io::prelude::*;
+
+use crate::dialect::Dialect;
+use crate::models::*;
+use crate::writer::TableWriter;
+use crate::Arguments;
+
+/// Writes the rows of a table as multi row insert queries.
+pub struct SqlWriter {
+    file: Box<dyn Write + Send>,
+    table: Table,
+    columns: Vec<String>,
+    dialect: Dialect,
+    ignore: bool,
+    maximum_rows: i32,
+    query_start: String,
+    query_end: &'static str,
+    count: i32,
+    query_length: usize,
+}
+
+impl SqlWriter {
+    pub fn new(mut file: Box<dyn Write + Send>, table: Table, arguments: &Arguments) -> SqlWriter {
+        let dialect = arguments.dialect;
+        let ignore = !arguments.no_ignore;
+        let columns = table.get_columns();
+
+        let w = write!(&mut file, "{}", dialect.create_table_query(&table));
+        w.unwrap();
+
+        SqlWriter {
+            file,
+            query_start: dialect.insert_query_start(&table, ignore),
+            query_end: dialect.insert_query_end(ignore),
+            maximum_rows: dialect.maximum_rows(arguments.maximum_rows),
+            table,
+            columns,
+            dialect,
+            ignore,
+            count: 0,
+            query_length: 0,
+        }
+    }
+}
+
+impl TableWriter for SqlWriter {
+    fn write_row(&mut self, data_set: &HashMap<&str, SqlType>) {
+        let mut values = String::from("(");
+        for (i, column) in self.columns.iter().enumerate() {
+            if i != 0 {
+                values += ","
+            }
+
+            let value = data_set.get(column.as_str()).unwrap_or(&SqlType::Null);
+            values += &self.dialect.value(value);
+        }
+        values += ")";
+
+        let too_long = self.dialect.maximum_query_length().is_some_and(|maximum| {
+            self.query_length + 1 + values.len() + self.query_end.len() > maximum
+        });
+        if self.count > 0 && (self.count >= self.maximum_rows || too_long) {
+            let w = write!(&mut self.file, "{}", self.query_end);
+            w.unwrap();
+            self.count = 0;
+        }
+
+        if self.count == 0 {
+            let w = write!(&mut self.file, "{}", self.query_start);
+            w.unwrap();
+            self.query_length = self.query_start.len();
+        } else {
+            let w = write!(&mut self.file, ",");
+            w.unwrap();
+            self.query_length += 1;
+        }
+
+        let w = write!(&mut self.file, "{}", values);
+        w.unwrap();
+        self.query_length += values.len();
+        self.count += 1;
+    }
+
+    fn finish(&mut self) {
+        if self.count > 0 {
+            let w = write!(&mut self.file, "{}", self.query_end);
+            w.unwrap();
+        }
+        let w = write!(
+            &mut self.file,
+            "{}",
+            self.dialect.finish_table_query(&self.table, self.ignore)
+        );
+        w.unwrap();
+        self.file.flush().unwrap();
+    }
+}
@@ -0,0 +1,149 @@
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::dialect::Dialect;
+use crate::models::*;
+use crate::writer::TableWriter;
+use crate::Arguments;
+
+/// Rows of a table inserted in one transaction.
+const TRANSACTION_ROWS: usize = 10_000;
+
+/// SQLite database shared by the writer threads. SQLite allows only one
+/// writer at a time, so the threads are taking turns with their batches.
+pub type Database = Arc<Mutex<Connection>>;
+
+/// Opens or creates the database. The journal is kept in memory and the
+/// file is not synced while loading, so a failed import can leave a
+/// corrupted database behind. Foreign keys are not enforced because the
+/// tables are loaded at the same time and extracts are referring to
+/// elements out of the extract.
+pub fn open_database(path: &str) -> Database {
+    let connection =
+        Connection::open(path).unwrap_or_else(|_| panic!("Can not open the database {}.", path));
+    connection
+        .execute_batch(
+            "PRAGMA foreign_keys = OFF;
+            PRAGMA journal_mode = MEMORY;
+            PRAGMA synchronous = OFF;
+            PRAGMA cache_size = -65536;",
+        )
+        .expect("Can not configure the database.");
+
+    Arc::new(Mutex::new(connection))
+}
+
+/// Closes the database after all writers are finished.
+pub fn close_database(database: Database) {
+    let connection = Arc::try_unwrap(database)
+        .unwrap_or_else(|_| panic!("The database is still used by a writer."))
+        .into_inner()
+        .unwrap();
+    connection
+        .close()
+        .map_err(|(_, error)| error)
+        .expect("Can not close the database.");
+}
+
+/// Inserts the rows of a table into a SQLite database with a prepared
+/// statement.
+pub struct SqliteWriter {
+    database: Database,
+    table: Table,
+    /// Whether the table is created by this writer. Indexes are only
+    /// created for new tables.
+    created: bool,
+    columns: Vec<String>,
+    query: String,
+    rows: Vec<Vec<Value>>,
+}
+
+impl SqliteWriter {
+    pub fn new(database: Database, table: Table, arguments: &Arguments) -> SqliteWriter {
+        let dialect = Dialect::Sqlite;
+        let columns = table.get_columns();
+
+        let created = {
+            let connection = database.lock().unwrap();
+            let exists: bool = connection
+                .query_row(
+                    "SELECT count(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?",
+                    [table.name],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            if !exists {
+                connection
+                    .execute_batch(&dialect.create_table_query(&table))
+                    .unwrap_or_else(|e| panic!("Can not create the table {}: {}", table.name, e));
+            }
+            !exists
+        };
+
+        SqliteWriter {
+            query: format!(
+                "{}({})",
+                dialect.insert_query_start(&table, !arguments.no_ignore),
+                vec!["?"; columns.len()].join(",")
+            ),
+            database,
+            table,
+            created,
+            columns,
+            rows: Vec::with_capacity(TRANSACTION_ROWS),
+        }
+    }
+
+    fn commit(&mut self) {
+        let mut connection = self.database.lock().unwrap();
+        let transaction = connection.transaction().unwrap();
+        {
+            let mut statement = transaction.prepare_cached(&self.query).unwrap();
+            for row in self.rows.drain(..) {
+                statement
+                    .execute(params_from_iter(row))
+                    .unwrap_or_else(|e| panic!("Can not insert a row: {}", e));
+            }
+        }
+        transaction.commit().unwrap();
+    }
+}
+
+impl TableWriter for SqliteWriter {
+    fn write_row(&mut self, data_set: &HashMap<&str, SqlType>) {
+        let row = self
+            .columns
+            .iter()
+            .map(
+                |column| match data_set.get(column.as_str()).unwrap_or(&SqlType::Null) {
+                    SqlType::BigInt(big_int) => Value::Integer(*big_int),
+                    SqlType::Int(int) => Value::Integer(i64::from(*int)),
+                    SqlType::Decimal(coordinate) => Value::Real(coordinate.to_f64()),
+                    SqlType::Varchar(varchar) => Value::Text(String::from(*varchar)),
+                    SqlType::Bool(b) => Value::Integer(i64::from(*b)),
+                    SqlType::Null => Value::Null,
+                },
+            )
+            .collect();
+        self.rows.push(row);
+
+        if self.rows.len() >= TRANSACTION_ROWS {
+            self.commit();
+        }
+    }
+
+    fn finish(&mut self) {
+        if !self.rows.is_empty() {
+            self.commit();
+        }
+        if self.created {
+            let query = Dialect::Sqlite.finish_table_query(&self.table, true);
+            let connection = self.database.lock().unwrap();
+            connection
+                .execute_batch(&query)
+                .unwrap_or_else(|e| panic!("Can not finish the table {}: {}", self.table.name, e));
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::fmt::Write;
+
+use crate::dialect::Dialect;
+use crate::escape::NUL_REPLACEMENT;
+use crate::models::{ColumnType, ColumnValue};
+
+/// Storage of the element tags.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TagStorage {
+    /// Tag keys in the tags table and the values in the ref_tags table.
+    Table,
+    /// JSON object in the tags column of the nodes, ways and relations.
+    Json,
+    /// PostgreSQL hstore in the tags column of the nodes, ways and
+    /// relations.
+    Hstore,
+}
+
+impl TagStorage {
+    pub const NAMES: [&'static str; 3] = ["table", "json", "hstore"];
+
+    pub fn from_name(name: &str) -> Option<TagStorage> {
+        match name {
+            "table" => Some(TagStorage::Table),
+            "json" => Some(TagStorage::Json),
+            "hstore" => Some(TagStorage::Hstore),
+            _ => None,
+        }
+    }
+
+    /// Type of the tags column. The elements have no tags column when the
+    /// tags are written to the tables.
+    pub fn column_type(&self) -> Option<ColumnType> {
+        match self {
+            TagStorage::Table => None,
+            TagStorage::Json => Some(ColumnType::Json),
+            TagStorage::Hstore => Some(ColumnType::Hstore),
+        }
+    }
+
+    /// Encodes the tags for the tags column. PostgreSQL can not store nul
+    /// characters in jsonb and hstore values, so they are replaced like in
+    /// the text columns.
+    pub fn encode(&self, tags: &[(String, String)], dialect: Dialect) -> String {
+        let replace_nul = dialect == Dialect::Postgres;
+        let mut encoded = String::new();
+
+        match self {
+            TagStorage::Table => panic!("Tags of the table storage are not encoded."),
+            TagStorage::Json => {
+                encoded.push('{');
+                for (i, (k, v)) in tags.iter().enumerate() {
+                    if i > 0 {
+                        encoded.push(',');
+                    }
+                    json_string(&mut encoded, k, replace_nul);
+                    encoded.push(':');
+                    json_string(&mut encoded, v, replace_nul);
+                }
+                encoded.push('}');
+            }
+            TagStorage::Hstore => {
+                for (i, (k, v)) in tags.iter().enumerate() {
+                    if i > 0 {
+                        encoded.push_str(", ");
+                    }
+                    hstore_string(&mut encoded, k);
+                    encoded.push_str("=>");
+                    hstore_string(&mut encoded, v);
+                }
+            }
+        }
+
+        encoded
+    }
+}
+
+fn json_string(out: &mut String, value: &str, replace_nul: bool) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\0' if replace_nul => out.push(NUL_REPLACEMENT),
+            c if c < ' ' => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn hstore_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\0' => out.push(NUL_REPLACEMENT),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Columns of the nodes, ways and relations that a tag can not be written to.
+const RESERVED_COLUMNS: [&str; 11] = [
+    "id",
+    "lat",
+    "lng",
+    "version",
+    "changeset",
+    "user",
+    "uid",
+    "visible",
+    "timestamp",
+    "tags",
+    "geom",
+];
+
+/// Tag key that is written to its own column of the nodes, ways and
+/// relations instead of the tag storage.
+#[derive(Clone, Debug)]
+pub struct TagColumn {
+    pub key: String,
+    pub column_type: ColumnType,
+}
+
+impl TagColumn {
+    /// Parses a comma separated list of keys with optional types like
+    /// `name,highway,layer=integer`.
+    pub fn parse_list(list: &str) -> Result<Vec<TagColumn>, String> {
+        let mut columns: Vec<TagColumn> = vec![];
+
+        for item in list.split(',').filter(|item| !item.is_empty()) {
+            let (key, column_type) = match item.rfind('=') {
+                Some(position) => (&item[..position], &item[position + 1..]),
+                None => (item, "text"),
+            };
+            let column_type = match column_type {
+                "text" => ColumnType::Varchar,
+                "integer" => ColumnType::Int,
+                _ => {
+                    return Err(format!(
+                        "Unknown type {} of the column {}. Possible types are text and integer.",
+                        column_type, key
+                    ))
+                }
+            };
+
+            if key.is_empty() || RESERVED_COLUMNS.contains(&key) {
+                return Err(format!("The tag {} can not be written to a column.", key));
+            }
+            if columns.iter().any(|column| column.key == key) {
+                return Err(format!("The column {} is given twice.", key));
+            }
+
+            columns.push(TagColumn {
+                key: String::from(key),
+                column_type,
+            });
+        }
+
+        Ok(columns)
+    }
+
+    /// Value of the tag in the column. Values that can not be stored in
+    /// the column type are left in the tag storage.
+    pub fn value(&self, value: &str) -> Option<ColumnValue> {
+        match self.column_type {
+            ColumnType::Int => value.parse().ok().map(ColumnValue::Integer),
+            _ => Some(ColumnValue::Text(String::from(value))),
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::env::temp_dir;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, prelude::*, BufWriter};
+use std::path::PathBuf;
+use std::process;
+use std::sync::mpsc::{channel, Sender};
+use std::thread::spawn;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::copy_writer::CopyWriter;
+use crate::csv_writer::CsvWriter;
+use crate::models::*;
+use crate::parquet_writer::ParquetWriter;
+use crate::sql_writer::SqlWriter;
+use crate::sqlite_writer::{Database, SqliteWriter};
+use crate::Arguments;
+
+pub enum ThreadSignal<T: Model> {
+    Write(T),
+    Stop,
+}
+
+/// Format of the output files.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// SQL files with create table and insert queries.
+    Sql,
+    /// PostgreSQL COPY text format files with a `load.sql` script.
+    Copy,
+    /// Comma separated values with a header line.
+    Csv,
+    /// Tab separated values with a header line.
+    Tsv,
+    /// Apache Parquet files.
+    Parquet,
+    /// Rows inserted into a SQLite database. Selected with `--database`.
+    Database,
+}
+
+impl Format {
+    pub const NAMES: [&'static str; 5] = ["sql", "copy", "csv", "tsv", "parquet"];
+
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "sql" => Some(Format::Sql),
+            "copy" => Some(Format::Copy),
+            "csv" => Some(Format::Csv),
+            "tsv" => Some(Format::Tsv),
+            "parquet" => Some(Format::Parquet),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Sql => "sql",
+            Format::Copy => "copy",
+            Format::Csv => "csv",
+            Format::Tsv => "tsv",
+            Format::Parquet => "parquet",
+            Format::Database => "sqlite",
+        }
+    }
+}
+
+/// Writes the rows of one table in an output format.
+pub trait TableWriter {
+    fn write_row(&mut self, data_set: &HashMap<&str, SqlType>);
+
+    /// Called after the last row. Should flush the output.
+    fn finish(&mut self);
+}
+
+/// Tables in the order that they should be imported.
+pub const TABLES: [&str; 8] = [
+    "nodes",
+    "ways",
+    "way_nodes",
+    "relations",
+    "relation_members",
+    "tags",
+    "tag_values",
+    "ref_tags",
+];
+
+/// Whether all tables should be written to the standard output instead of
+/// separate files.
+pub fn is_stdout(arguments: &Arguments) -> bool {
+    arguments.output == "-"
+}
+
+fn spool_path(table: &str) -> PathBuf {
+    temp_dir().join(format!("osm-to-sql-{}-{}", process::id(), table))
+}
+
+/// Temporary file of a table that is waiting to be written to the standard
+/// output. The file is removed when it is dropped.
+pub struct SpoolFile {
+    table: &'static str,
+    path: PathBuf,
+}
+
+impl SpoolFile {
+    /// Spool files of the tables that are not written to the standard
+    /// output directly.
+    pub fn for_tables(tables: &[Table]) -> Vec<SpoolFile> {
+        tables
+            .iter()
+            .filter(|table| table.name != TABLES[0])
+            .map(|table| SpoolFile {
+                table: table.name,
+                path: spool_path(table.name),
+            })
+            .collect()
+    }
+}
+
+impl Drop for SpoolFile {
+    fn drop(&mut self) {
+        // The spooled table is a temporary file even if the conversion failed.
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Opens the output of a table. When writing to the standard output only
+/// the first table is written there directly. Others are spooled to
+/// temporary files until the previous tables are finished.
+fn open_output(arguments: &Arguments, table: &str) -> Box<dyn Write + Send> {
+    if is_stdout(arguments) && table == TABLES[0] {
+        return Box::new(BufWriter::new(io::stdout()));
+    }
+
+    let file_path = if is_stdout(arguments) {
+        spool_path(table)
+    } else {
+        PathBuf::from(&arguments.output).join(format!("{}.{}", table, arguments.format.extension()))
+    };
+
+    let file = OpenOptions::new()
+        .append(true)
+        .create_new(true)
+        .open(file_path)
+        .unwrap_or_else(|_| {
+            panic!(
+                "Can not open the file {}. Already exist or permission denied",
+                table
+            )
+        });
+
+    Box::new(BufWriter::new(file))
+}
+
+/// Writes the spooled tables to the standard output in the import order.
+/// Every spool file is removed after it is written.
+pub fn write_spooled(spool_files: Vec<SpoolFile>) {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    for spool_file in spool_files {
+        let mut file = File::open(&spool_file.path).unwrap_or_else(|e| {
+            panic!("Can not open the spooled {} table: {}", spool_file.table, e)
+        });
+        io::copy(&mut file, &mut stdout)
+            .and_then(|_| stdout.flush())
+            .expect("Can not write to the standard output.");
+    }
+}
+
+/// Starts the writer thread of a table. The database is only used by the
+/// database format.
+pub fn new_thread<T: Model + Send + 'static>(
+    arguments: Arguments,
+    database: Option<Database>,
+) -> (JoinHandle<()>, Sender<ThreadSignal<T>>) {
+    let (snd, rcv) = channel::<ThreadSignal<T>>();
+    let handle = spawn(move || {
+        let file = || open_output(&arguments, T::get_table_name());
+        let table = T::get_table(&arguments);
+        let mut writer: Box<dyn TableWriter> = match arguments.format {
+            Format::Sql => Box::new(SqlWriter::new(file(), table, &arguments)),
+            Format::Copy => Box::new(CopyWriter::new(file(), table)),
+            Format::Csv => Box::new(CsvWriter::new(file(), table, ',')),
+            Format::Tsv => Box::new(CsvWriter::new(file(), table, '\t')),
+            Format::Parquet => Box::new(ParquetWriter::new(file(), table, &arguments)),
+            Format::Database => Box::new(SqliteWriter::new(
+                database.expect("The database is not opened."),
+                table,
+                &arguments,
+            )),
+        };
+
+        loop {
+            if let Ok(result) = rcv.recv_timeout(Duration::from_secs(1)) {
+                match result {
+                    ThreadSignal::Write(entry) => {
+                        writer.write_row(&entry.get_data_set());
+                    }
+                    ThreadSignal::Stop => {
+                        writer.finish();
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    (handle, snd)
+}
@@ -0,0 +1,151 @@
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::str;
+
+use crate::handler::Handler;
+use crate::models::*;
+
+/// Node, way or relation that is read until its end tag.
+enum Element {
+    Node(Node),
+    Way(Way),
+    Relation(Relation),
+}
+
+impl Element {
+    fn main_info(&mut self) -> &mut MainInfo {
+        match self {
+            Element::Node(node) => &mut node.main_info,
+            Element::Way(way) => &mut way.main_info,
+            Element::Relation(relation) => &mut relation.main_info,
+        }
+    }
+}
+
+/// Reads an OSM XML document and passes every element to the handler.
+/// Elements are passed at their end tags with all of their tags, nodes
+/// and members.
+pub fn read<B: BufRead>(mut reader: Reader<B>, handler: &mut dyn Handler) {
+    let mut buf = vec![];
+    let mut element: Option<Element> = None;
+
+    // Self closing tags
+    reader.expand_empty_elements(true);
+
+    loop {
+        match reader.read_event(&mut buf) {
+            Ok(Event::Start(e)) => {
+                let name = e.name();
+                let attrs: HashMap<String, String> = e
+                    .attributes()
+                    .map(|a| {
+                        let attr = a.unwrap();
+                        let value = String::from_utf8(attr.unescaped_value().unwrap().into_owned())
+                            .unwrap();
+
+                        (str::from_utf8(attr.key).unwrap().to_string(), value)
+                    })
+                    .collect();
+
+                match name {
+                    b"node" => {
+                        let mut node: Node = Node {
+                            ..Default::default()
+                        };
+
+                        for (key, value) in attrs {
+                            if !node.main_info.set_attribute(key.clone(), value.clone()) {
+                                match key.as_str() {
+                                    "lat" => {
+                                        node.lat = Coordinate::parse(&value)
+                                            .expect("Can not read the lat attribute of a node.");
+                                    }
+                                    "lon" => {
+                                        node.lng = Coordinate::parse(&value)
+                                            .expect("Can not read the lon attribute of a node.");
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+
+                        element = Some(Element::Node(node));
+                    }
+                    b"way" => {
+                        let mut way: Way = Way {
+                            ..Default::default()
+                        };
+                        for (k, v) in attrs {
+                            way.main_info.set_attribute(k, v);
+                        }
+
+                        element = Some(Element::Way(way));
+                    }
+                    b"relation" => {
+                        let mut relation: Relation = Relation {
+                            ..Default::default()
+                        };
+                        for (k, v) in attrs {
+                            relation.main_info.set_attribute(k, v);
+                        }
+
+                        element = Some(Element::Relation(relation));
+                    }
+                    b"tag" => {
+                        let k = String::from(attrs.get("k").unwrap());
+                        let v = String::from(attrs.get("v").unwrap());
+
+                        if let Some(element) = element.as_mut() {
+                            element.main_info().tags.push((k, v));
+                        }
+                    }
+                    b"nd" => {
+                        let ref_attr = attrs
+                            .get("ref")
+                            .expect("Can not read the ref attribute from nd tag.");
+
+                        if let Some(Element::Way(way)) = element.as_mut() {
+                            way.nodes.push(ref_attr.parse::<i64>().unwrap());
+                        }
+                    }
+                    b"member" => {
+                        let ref_attr = attrs
+                            .get("ref")
+                            .expect("Can not read ref attr from member tag.");
+                        let type_attr = attrs
+                            .get("type")
+                            .expect("Can not read type attr from member tag.");
+                        let role_attr = attrs.get("role").unwrap();
+
+                        if let Some(Element::Relation(relation)) = element.as_mut() {
+                            relation.members.push(RelationMember {
+                                ref_type: type_attr.clone(),
+                                ref_id: ref_attr.parse::<i64>().unwrap(),
+                                role: role_attr.clone(),
+                                relation_id: relation.main_info.id,
+                                sequence_id: relation.members.len() as i32,
+                            });
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            Ok(Event::End(e)) => {
+                if let b"node" | b"way" | b"relation" = e.name() {
+                    match element.take() {
+                        Some(Element::Node(node)) => handler.node(node),
+                        Some(Element::Way(way)) => handler.way(way),
+                        Some(Element::Relation(relation)) => handler.relation(relation),
+                        None => {}
+                    }
+                }
+            }
+            Err(e) => panic!("Error at position {}: {:?}", reader.buffer_position(), e),
+            Ok(Event::Eof) => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+}
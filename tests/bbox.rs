@@ -0,0 +1,139 @@
+mod common;
+
+use common::*;
+use rusqlite::Connection;
+
+/// Nodes 1 and 5 are in the box 0,0,1,1. Way 10 is crossing the border,
+/// way 11 is outside and both are outer ways of the multipolygon 20.
+/// Relation 22 is referring to relation 23 that has node 5.
+fn input() -> String {
+    let mut xml = String::new();
+    for (id, lat, lon) in [
+        (1, "0.5", "0.5"),
+        (2, "2", "0.5"),
+        (3, "2", "2"),
+        (4, "0.5", "2"),
+        (5, "1", "1"),
+    ]
+    .iter()
+    {
+        xml += &node_xml(*id, lat, lon);
+    }
+    xml += &way_xml(10, &[1, 2], &[]);
+    xml += &way_xml(11, &[3, 4], &[("name", "outside")]);
+    xml += &relation_xml(
+        20,
+        &[("way", 11, "outer"), ("way", 10, "outer")],
+        &[("type", "multipolygon")],
+    );
+    xml += &relation_xml(21, &[("node", 2, "")], &[]);
+    xml += &relation_xml(22, &[("relation", 23, "")], &[]);
+    xml += &relation_xml(23, &[("node", 5, "")], &[]);
+
+    String::from(write_xml("bbox-input", &xml).to_str().unwrap())
+}
+
+/// Converts the input to a database with the strategy and checks that all
+/// foreign keys are referring to written rows.
+fn extract(strategy: &str) -> Connection {
+    let directory = output_directory(&format!("bbox-{}", strategy));
+    let database = directory.join("osm.db");
+    run(&[
+        "-i",
+        &input(),
+        "--database",
+        database.to_str().unwrap(),
+        "--bbox",
+        "0,0,1,1",
+        "--strategy",
+        strategy,
+    ]);
+
+    let connection = Connection::open(&database).unwrap();
+    assert!(query_column(&connection, "PRAGMA foreign_key_check").is_empty());
+    connection
+}
+
+fn elements(connection: &Connection) -> [Vec<String>; 5] {
+    [
+        query_column(connection, "SELECT id FROM nodes ORDER BY id"),
+        query_column(connection, "SELECT id FROM ways ORDER BY id"),
+        query_column(
+            connection,
+            "SELECT way_id || ':' || node_id FROM way_nodes ORDER BY way_id, sequence_id",
+        ),
+        query_column(connection, "SELECT id FROM relations ORDER BY id"),
+        query_column(
+            connection,
+            "SELECT relation_id || ':' || coalesce(node_id, way_id, sub_relation_id) || ':' || sequence_id FROM relation_members ORDER BY relation_id, sequence_id",
+        ),
+    ]
+}
+
+#[test]
+fn simple_strategy_drops_the_nodes_outside() {
+    assert_eq!(
+        elements(&extract("simple")),
+        [
+            vec!["1", "5"],
+            vec!["10"],
+            vec!["10:1"],
+            vec!["20", "23"],
+            vec!["20:10:0", "23:5:0"],
+        ]
+    );
+}
+
+#[test]
+fn complete_ways_strategy_keeps_the_nodes_of_the_ways() {
+    assert_eq!(
+        elements(&extract("complete_ways")),
+        [
+            vec!["1", "2", "5"],
+            vec!["10"],
+            vec!["10:1", "10:2"],
+            vec!["20", "22", "23"],
+            vec!["20:10:0", "22:23:0", "23:5:0"],
+        ]
+    );
+}
+
+#[test]
+fn smart_strategy_keeps_the_multipolygon_ways() {
+    let connection = extract("smart");
+    assert_eq!(
+        elements(&connection),
+        [
+            vec!["1", "2", "3", "4", "5"],
+            vec!["10", "11"],
+            vec!["10:1", "10:2", "11:3", "11:4"],
+            vec!["20", "22", "23"],
+            vec!["20:11:0", "20:10:1", "22:23:0", "23:5:0"],
+        ]
+    );
+    assert_eq!(
+        query_column(&connection, "SELECT value FROM ref_tags WHERE way_id = 11"),
+        ["outside"]
+    );
+}
+
+#[test]
+fn simple_strategy_reads_the_standard_input() {
+    let input = std::fs::read(input()).unwrap();
+    let output = run_with_stdin(
+        &[
+            "-i",
+            "-",
+            "-d",
+            "-",
+            "--bbox",
+            "0,0,1,1",
+            "--strategy",
+            "simple",
+        ],
+        &input,
+    );
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("VALUES (1,0.5,0.5,"));
+    assert!(!output.contains("VALUES (2,"));
+}
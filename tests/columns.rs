@@ -0,0 +1,160 @@
+mod common;
+
+use common::pbf::PbfWriter;
+use common::sql::parse_rows;
+use common::*;
+use std::path::Path;
+
+/// Columns of a table that are only filled for one element type. Exactly
+/// one of them should have a value in every row.
+fn reference_columns(table: &str) -> &'static [&'static str] {
+    match table {
+        "ref_tags" => &["node_id", "relation_id", "way_id"],
+        "relation_members" => &["node_id", "way_id", "sub_relation_id"],
+        _ => &[],
+    }
+}
+
+/// Column names of the insert queries of a table.
+fn columns(sql: &str) -> Vec<String> {
+    let start = sql.find("INTO ").unwrap();
+    let start = start + sql[start..].find('(').unwrap() + 1;
+    let end = start + sql[start..].find(") VALUES ").unwrap();
+    sql[start..end]
+        .split(',')
+        .map(|column| column.trim_matches(|c| c == '"' || c == '`').to_string())
+        .collect()
+}
+
+/// Checks that every column of every table has a value unless it belongs
+/// to another element type.
+fn assert_populated(directory: &Path, dialect: &str) {
+    for table in TABLES.iter() {
+        let sql = read_table(directory, table);
+        let columns = columns(&sql);
+        let rows = parse_rows(&sql, dialect);
+        assert!(!rows.is_empty(), "{} has no rows", table);
+
+        for row in rows.iter() {
+            assert_eq!(row.len(), columns.len());
+            let mut references = 0;
+            for (column, value) in columns.iter().zip(row.iter()) {
+                if reference_columns(table).contains(&column.as_str()) {
+                    references += value.is_some() as usize;
+                } else {
+                    assert!(
+                        value.is_some(),
+                        "{}.{} is NULL in {:?} ({})",
+                        table,
+                        column,
+                        row,
+                        dialect
+                    );
+                }
+            }
+            if !reference_columns(table).is_empty() {
+                assert_eq!(references, 1, "{} row {:?}", table, row);
+            }
+        }
+
+        for column in reference_columns(table).iter() {
+            let index = columns.iter().position(|c| c == column).unwrap();
+            assert!(
+                rows.iter().any(|row| row[index].is_some()),
+                "{}.{} is never populated ({})",
+                table,
+                column,
+                dialect
+            );
+        }
+    }
+}
+
+fn row(values: &[Option<&str>]) -> Vec<Option<String>> {
+    values.iter().map(|value| value.map(String::from)).collect()
+}
+
+/// Tag values written for the elements of both inputs.
+fn assert_ref_tags(directory: &Path, dialect: &str) {
+    assert_eq!(
+        parse_rows(&read_table(directory, "ref_tags"), dialect),
+        vec![
+            row(&[Some("0"), Some("1"), None, None, Some("crossing")]),
+            row(&[Some("1"), Some("1"), None, None, Some("Main Street")]),
+            row(&[Some("0"), None, None, Some("10"), Some("residential")]),
+            row(&[Some("2"), None, Some("100"), None, Some("route")]),
+        ],
+        "{}",
+        dialect
+    );
+}
+
+#[test]
+fn every_column_is_populated_from_xml() {
+    let input = write_xml(
+        "columns-xml-input",
+        &(node_xml(2, "1", "2")
+            + &tagged_node_xml(
+                1,
+                "1.5",
+                "2.5",
+                &[("highway", "crossing"), ("name", "Main Street")],
+            )
+            + &way_xml(10, &[1, 2], &[("highway", "residential")])
+            + &relation_xml(
+                100,
+                &[
+                    ("node", 1, "stop"),
+                    ("way", 10, ""),
+                    ("relation", 101, "sub"),
+                ],
+                &[("type", "route")],
+            )),
+    );
+
+    for dialect in ["mysql", "postgres", "sqlite"].iter() {
+        let directory = convert(
+            "columns-xml",
+            input.to_str().unwrap(),
+            &["--dialect", dialect],
+        );
+        assert_populated(&directory, dialect);
+        assert_ref_tags(&directory, dialect);
+    }
+}
+
+#[test]
+fn every_column_is_populated_from_pbf() {
+    let mut pbf = PbfWriter::new();
+    pbf.node(
+        1,
+        15,
+        25,
+        "u",
+        &[("highway", "crossing"), ("name", "Main Street")],
+    );
+    pbf.node(2, 10, 20, "u", &[]);
+    pbf.way(10, "u", &[1, 2], &[("highway", "residential")]);
+    pbf.relation(
+        100,
+        "u",
+        &[
+            ("node", 1, "stop"),
+            ("way", 10, ""),
+            ("relation", 101, "sub"),
+        ],
+        &[("type", "route")],
+    );
+    let input = output_directory("columns-pbf-input").join("input.osm.pbf");
+    pbf.write(&input);
+
+    for dialect in ["mysql", "postgres", "sqlite"].iter() {
+        let directory = convert(
+            "columns-pbf",
+            input.to_str().unwrap(),
+            &["--dialect", dialect],
+        );
+        assert_populated(&directory, dialect);
+        assert_ref_tags(&directory, dialect);
+    }
+}
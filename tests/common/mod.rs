@@ -0,0 +1,176 @@
+// Helpers are shared between the test crates and not all of them are used
+// in every crate.
+#![allow(dead_code)]
+
+pub mod pbf;
+pub mod sql;
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+use rusqlite::types::Value;
+use rusqlite::Connection;
+
+/// Creates an empty output directory for a test case.
+pub fn output_directory(name: &str) -> PathBuf {
+    let directory =
+        std::env::temp_dir().join(format!("osm-to-sql-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+/// Runs the command with the given arguments and panics when it fails.
+pub fn run(args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_osm-to-sql"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "osm-to-sql {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+/// Converts the input file and returns the output directory.
+pub fn convert(name: &str, input: &str, args: &[&str]) -> PathBuf {
+    let directory = output_directory(name);
+    let mut all_args = vec!["-i", input, "-d", directory.to_str().unwrap()];
+    all_args.extend_from_slice(args);
+    run(&all_args);
+    directory
+}
+
+pub const TABLES: [&str; 7] = [
+    "nodes",
+    "ways",
+    "way_nodes",
+    "relations",
+    "relation_members",
+    "tags",
+    "ref_tags",
+];
+
+pub fn read_table(directory: &Path, table: &str) -> String {
+    fs::read_to_string(directory.join(format!("{}.sql", table))).unwrap()
+}
+
+/// Runs the command with the given standard input and returns the
+/// standard output.
+pub fn run_with_stdin(args: &[&str], stdin: &[u8]) -> Vec<u8> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_osm-to-sql"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(
+        output.status.success(),
+        "osm-to-sql {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output.stdout
+}
+
+/// Writes an OSM XML file with the given elements and returns its path.
+pub fn write_xml(name: &str, elements: &str) -> PathBuf {
+    let path = output_directory(name).join("input.osm");
+    fs::write(
+        &path,
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<osm version=\"0.6\">\n{}</osm>\n",
+            elements
+        ),
+    )
+    .unwrap();
+    path
+}
+
+/// Metadata attributes of the elements in the XML inputs.
+pub const METADATA: &str =
+    "user=\"u\" uid=\"1\" visible=\"true\" version=\"1\" changeset=\"1\" timestamp=\"2020-01-01T00:00:00Z\"";
+
+/// A node element without tags.
+pub fn node_xml(id: i64, lat: &str, lon: &str) -> String {
+    format!(
+        " <node id=\"{}\" lat=\"{}\" lon=\"{}\" {}/>\n",
+        id, lat, lon, METADATA
+    )
+}
+
+fn tags_xml(tags: &[(&str, &str)]) -> String {
+    tags.iter()
+        .map(|(k, v)| format!("<tag k=\"{}\" v=\"{}\"/>", k, v))
+        .collect()
+}
+
+/// A node element with tags.
+pub fn tagged_node_xml(id: i64, lat: &str, lon: &str, tags: &[(&str, &str)]) -> String {
+    format!(
+        " <node id=\"{}\" lat=\"{}\" lon=\"{}\" {}>{}</node>\n",
+        id,
+        lat,
+        lon,
+        METADATA,
+        tags_xml(tags)
+    )
+}
+
+/// A way element with the node ids and tags.
+pub fn way_xml(id: i64, nodes: &[i64], tags: &[(&str, &str)]) -> String {
+    let nodes: String = nodes
+        .iter()
+        .map(|node| format!("<nd ref=\"{}\"/>", node))
+        .collect();
+    format!(
+        " <way id=\"{}\" {}>{}{}</way>\n",
+        id,
+        METADATA,
+        nodes,
+        tags_xml(tags)
+    )
+}
+
+/// A relation element with the members as (type, ref, role) and tags.
+pub fn relation_xml(id: i64, members: &[(&str, i64, &str)], tags: &[(&str, &str)]) -> String {
+    let members: String = members
+        .iter()
+        .map(|(member_type, ref_id, role)| {
+            format!(
+                "<member type=\"{}\" ref=\"{}\" role=\"{}\"/>",
+                member_type, ref_id, role
+            )
+        })
+        .collect();
+    format!(
+        " <relation id=\"{}\" {}>{}{}</relation>\n",
+        id,
+        METADATA,
+        members,
+        tags_xml(tags)
+    )
+}
+
+/// Values of the first column of the query. Integers are returned as text.
+pub fn query_column(connection: &Connection, query: &str) -> Vec<String> {
+    let mut statement = connection.prepare(query).unwrap();
+    let rows = statement
+        .query_map([], |row| row.get::<_, Value>(0))
+        .unwrap();
+    rows.map(|row| match row.unwrap() {
+        Value::Integer(integer) => integer.to_string(),
+        Value::Text(text) => text,
+        value => panic!("Unexpected value {:?}", value),
+    })
+    .collect()
+}
@@ -0,0 +1,179 @@
+use std::fs;
+use std::path::Path;
+
+fn varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn field_varint(out: &mut Vec<u8>, number: u64, value: u64) {
+    varint(out, number << 3);
+    varint(out, value);
+}
+
+fn field_bytes(out: &mut Vec<u8>, number: u64, value: &[u8]) {
+    varint(out, (number << 3) | 2);
+    varint(out, value.len() as u64);
+    out.extend_from_slice(value);
+}
+
+fn field_packed(out: &mut Vec<u8>, number: u64, values: &[u64]) {
+    let mut packed = vec![];
+    for value in values {
+        varint(&mut packed, *value);
+    }
+    field_bytes(out, number, &packed);
+}
+
+fn delta(values: &[i64]) -> Vec<u64> {
+    let mut last = 0;
+    values
+        .iter()
+        .map(|value| {
+            let encoded = zigzag(value - last);
+            last = *value;
+            encoded
+        })
+        .collect()
+}
+
+/// Builds a small uncompressed PBF file with plain (not dense) nodes.
+#[derive(Default)]
+pub struct PbfWriter {
+    strings: Vec<String>,
+    nodes: Vec<u8>,
+    ways: Vec<u8>,
+    relations: Vec<u8>,
+    /// Whether the elements are written without their info messages.
+    without_info: bool,
+}
+
+impl PbfWriter {
+    pub fn new() -> PbfWriter {
+        PbfWriter {
+            strings: vec![String::new()],
+            ..Default::default()
+        }
+    }
+
+    /// Writes the elements without version, timestamp, changeset, user and
+    /// visibility like some extracts.
+    pub fn without_info() -> PbfWriter {
+        PbfWriter {
+            without_info: true,
+            ..PbfWriter::new()
+        }
+    }
+
+    fn string(&mut self, value: &str) -> u64 {
+        match self.strings.iter().position(|s| s == value) {
+            Some(index) => index as u64,
+            None => {
+                self.strings.push(String::from(value));
+                (self.strings.len() - 1) as u64
+            }
+        }
+    }
+
+    /// Node ids are sint64 unlike the ids of ways and relations. So the
+    /// id is passed encoded.
+    fn common(&mut self, out: &mut Vec<u8>, id: u64, user: &str, tags: &[(&str, &str)]) {
+        let keys: Vec<u64> = tags.iter().map(|(k, _)| self.string(k)).collect();
+        let values: Vec<u64> = tags.iter().map(|(_, v)| self.string(v)).collect();
+        let mut info = vec![];
+        field_varint(&mut info, 1, 1);
+        field_varint(&mut info, 2, 1_577_836_800);
+        field_varint(&mut info, 3, 1);
+        field_varint(&mut info, 4, 1);
+        field_varint(&mut info, 5, self.string(user));
+
+        field_varint(out, 1, id);
+        field_packed(out, 2, &keys);
+        field_packed(out, 3, &values);
+        if !self.without_info {
+            field_bytes(out, 4, &info);
+        }
+    }
+
+    /// Coordinates are in 100 nanodegree units.
+    pub fn node(&mut self, id: i64, lat: i64, lon: i64, user: &str, tags: &[(&str, &str)]) {
+        let mut node = vec![];
+        self.common(&mut node, zigzag(id), user, tags);
+        field_varint(&mut node, 8, zigzag(lat));
+        field_varint(&mut node, 9, zigzag(lon));
+        field_bytes(&mut self.nodes, 1, &node);
+    }
+
+    pub fn way(&mut self, id: i64, user: &str, refs: &[i64], tags: &[(&str, &str)]) {
+        let mut way = vec![];
+        self.common(&mut way, id as u64, user, tags);
+        field_packed(&mut way, 8, &delta(refs));
+        field_bytes(&mut self.ways, 3, &way);
+    }
+
+    pub fn relation(
+        &mut self,
+        id: i64,
+        user: &str,
+        members: &[(&str, i64, &str)],
+        tags: &[(&str, &str)],
+    ) {
+        let mut relation = vec![];
+        self.common(&mut relation, id as u64, user, tags);
+        let roles: Vec<u64> = members.iter().map(|(_, _, r)| self.string(r)).collect();
+        let ids: Vec<i64> = members.iter().map(|(_, id, _)| *id).collect();
+        let types: Vec<u64> = members
+            .iter()
+            .map(|(t, _, _)| match *t {
+                "node" => 0,
+                "way" => 1,
+                _ => 2,
+            })
+            .collect();
+        field_packed(&mut relation, 8, &roles);
+        field_packed(&mut relation, 9, &delta(&ids));
+        field_packed(&mut relation, 10, &types);
+        field_bytes(&mut self.relations, 4, &relation);
+    }
+
+    pub fn write(self, path: &Path) {
+        let mut strings = vec![];
+        for string in self.strings.iter() {
+            field_bytes(&mut strings, 1, string.as_bytes());
+        }
+        let mut block = vec![];
+        field_bytes(&mut block, 1, &strings);
+        for group in [&self.nodes, &self.ways, &self.relations].iter() {
+            if !group.is_empty() {
+                field_bytes(&mut block, 2, group);
+            }
+        }
+
+        let mut header = vec![];
+        field_bytes(&mut header, 4, b"OsmSchema-V0.6");
+
+        let mut file = vec![];
+        blob(&mut file, "OSMHeader", &header);
+        blob(&mut file, "OSMData", &block);
+        fs::write(path, file).unwrap();
+    }
+}
+
+fn blob(out: &mut Vec<u8>, block_type: &str, data: &[u8]) {
+    let mut blob = vec![];
+    field_bytes(&mut blob, 1, data);
+    let mut header = vec![];
+    field_bytes(&mut header, 1, block_type.as_bytes());
+    field_varint(&mut header, 3, blob.len() as u64);
+
+    out.extend_from_slice(&(header.len() as u32).to_be_bytes());
+    out.extend_from_slice(&header);
+    out.extend_from_slice(&blob);
+}
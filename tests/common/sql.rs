@@ -0,0 +1,121 @@
+/// Reads the rows of the insert queries in a SQL file of the dialect. Only
+/// the literals that the command is writing are supported. Numbers are
+/// returned as they are written and NULL values are `None`.
+pub fn parse_rows(sql: &str, dialect: &str) -> Vec<Vec<Option<String>>> {
+    let mut parser = Parser {
+        sql,
+        pos: 0,
+        dialect,
+    };
+    let mut rows = vec![];
+
+    while let Some(start) = sql[parser.pos..].find(" VALUES ") {
+        parser.pos += start + " VALUES ".len();
+        loop {
+            parser.expect("(");
+            let mut row = vec![];
+            loop {
+                row.push(parser.value());
+                match parser.next() {
+                    ',' => continue,
+                    ')' => break,
+                    c => panic!("Unexpected {:?} in a row", c),
+                }
+            }
+            rows.push(row);
+            if parser.peek() == Some(',') {
+                parser.next();
+            } else {
+                break;
+            }
+        }
+    }
+
+    rows
+}
+
+struct Parser<'a> {
+    sql: &'a str,
+    pos: usize,
+    dialect: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.sql[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> char {
+        let c = self.peek().expect("Unexpected end of the SQL");
+        self.pos += c.len_utf8();
+        c
+    }
+
+    fn expect(&mut self, expected: &str) {
+        assert!(
+            self.sql[self.pos..].starts_with(expected),
+            "Expected {:?} at {}",
+            expected,
+            &self.sql[self.pos..]
+        );
+        self.pos += expected.len();
+    }
+
+    fn value(&mut self) -> Option<String> {
+        match self.peek() {
+            Some('\'') | Some('E') => {
+                let mut value = self.string();
+                // SQLite control characters are concatenated.
+                while self.dialect == "sqlite" && self.peek() == Some('|') {
+                    self.expect("||char(");
+                    let start = self.pos;
+                    while self.next() != ')' {}
+                    let code = self.sql[start..self.pos - 1].parse().unwrap();
+                    value.push(std::char::from_u32(code).unwrap());
+                    self.expect("||");
+                    value += &self.string();
+                }
+                Some(value)
+            }
+            _ => {
+                let start = self.pos;
+                while self.peek() != Some(',') && self.peek() != Some(')') {
+                    self.next();
+                }
+                match &self.sql[start..self.pos] {
+                    "NULL" => None,
+                    value => Some(String::from(value)),
+                }
+            }
+        }
+    }
+
+    fn string(&mut self) -> String {
+        let backslashes = if self.peek() == Some('E') {
+            self.next();
+            true
+        } else {
+            self.dialect == "mysql"
+        };
+        self.expect("'");
+
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                '\'' if self.peek() == Some('\'') => {
+                    self.next();
+                    value.push('\'');
+                }
+                '\'' => return value,
+                '\\' if backslashes => value.push(match self.next() {
+                    '0' => '\0',
+                    'n' => '\n',
+                    'r' => '\r',
+                    'Z' => '\u{1a}',
+                    c => c,
+                }),
+                c => value.push(c),
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::*;
+
+fn assert_same_as_xml(name: &str, input: &str) {
+    let xml = convert(&format!("{}-xml", name), "sample/osm.xml", &[]);
+    let compressed = convert(name, input, &[]);
+
+    for table in TABLES.iter() {
+        assert_eq!(
+            read_table(&xml, table),
+            read_table(&compressed, table),
+            "{}",
+            table
+        );
+    }
+}
+
+#[test]
+fn bzip2_compressed_xml() {
+    assert_same_as_xml("bzip2", "sample/osm.xml.bz2");
+}
+
+#[test]
+fn gzip_compressed_xml() {
+    assert_same_as_xml("gzip", "sample/osm.xml.gz");
+}
+
+#[test]
+fn zstd_compressed_xml() {
+    assert_same_as_xml("zstd", "sample/osm.xml.zst");
+}
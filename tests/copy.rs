@@ -0,0 +1,96 @@
+mod common;
+
+use common::pbf::PbfWriter;
+use common::*;
+use std::fs;
+
+/// Reads the fields of a COPY text format file.
+fn parse_copy(text: &str) -> Vec<Vec<Option<String>>> {
+    text.lines()
+        .map(|line| {
+            line.split('\t')
+                .map(|field| {
+                    if field == "\\N" {
+                        return None;
+                    }
+                    let mut value = String::new();
+                    let mut chars = field.chars();
+                    while let Some(c) = chars.next() {
+                        if c != '\\' {
+                            value.push(c);
+                            continue;
+                        }
+                        value.push(match chars.next().unwrap() {
+                            'n' => '\n',
+                            'r' => '\r',
+                            't' => '\t',
+                            'b' => '\u{8}',
+                            'f' => '\u{c}',
+                            'v' => '\u{b}',
+                            c => c,
+                        });
+                    }
+                    Some(value)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn copy_files_and_load_script() {
+    let directory = convert("copy", "sample/osm.xml", &["--format", "copy"]);
+
+    let nodes = parse_copy(&fs::read_to_string(directory.join("nodes.copy")).unwrap());
+    assert_eq!(nodes.len(), 4);
+    assert_eq!(nodes[0][0].as_deref(), Some("298884269"));
+    assert_eq!(nodes[0][5].as_deref(), Some("SvenHRO"));
+    assert_eq!(nodes[0][7].as_deref(), Some("t"));
+
+    let members = parse_copy(&fs::read_to_string(directory.join("relation_members.copy")).unwrap());
+    assert_eq!(
+        members[2],
+        vec![
+            Some(String::from("56688")),
+            None,
+            Some(String::from("4579143")),
+            None,
+            Some(String::new()),
+            Some(String::from("2"))
+        ]
+    );
+
+    let script = fs::read_to_string(directory.join("load.sql")).unwrap();
+    let position = |text: &str| script.find(text).unwrap();
+    for (previous, next) in TABLES.iter().zip(TABLES.iter().skip(1)) {
+        assert!(
+            position(&format!("\\copy {} ", previous)) < position(&format!("\\copy {} ", next))
+        );
+    }
+    assert!(position("CREATE TABLE ref_tags") < position("\\copy nodes "));
+    assert!(position("\\copy ref_tags ") < position("ADD CONSTRAINT nodes_pk"));
+    assert!(position("ADD CONSTRAINT ref_tags_pk") < position("ADD CONSTRAINT way_nodes_nodes_fk"));
+    assert!(!script.contains("IGNORE"));
+}
+
+#[test]
+fn copy_fields_are_escaped() {
+    let hostile = "tab\tnew\nline\r back\\slash \\N nul\0";
+    let mut pbf = PbfWriter::new();
+    pbf.node(1, 10, 20, hostile, &[]);
+    let input = output_directory("copy-escape-input").join("input.osm.pbf");
+    pbf.write(&input);
+
+    let directory = convert(
+        "copy-escape",
+        input.to_str().unwrap(),
+        &["--format", "copy"],
+    );
+    let nodes = parse_copy(&fs::read_to_string(directory.join("nodes.copy")).unwrap());
+
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(
+        nodes[0][5].as_deref(),
+        Some("tab\tnew\nline\r back\\slash \\N nul\u{FFFD}")
+    );
+}
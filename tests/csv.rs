@@ -0,0 +1,137 @@
+mod common;
+
+use common::pbf::PbfWriter;
+use common::*;
+use std::fs;
+
+/// Reads the records of an RFC 4180 file. Unquoted empty fields are `None`.
+fn parse_csv(text: &str, delimiter: char) -> Vec<Vec<Option<String>>> {
+    let mut records = vec![];
+    let mut chars = text.chars().peekable();
+
+    while chars.peek().is_some() {
+        let mut record = vec![];
+        loop {
+            let mut field = String::new();
+            let mut quoted = false;
+            if chars.peek() == Some(&'"') {
+                quoted = true;
+                chars.next();
+                loop {
+                    match chars.next().expect("Unterminated quoted field") {
+                        '"' if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        '"' => break,
+                        c => field.push(c),
+                    }
+                }
+            }
+            while let Some(&c) = chars.peek() {
+                if c == delimiter || c == '\r' {
+                    break;
+                }
+                assert!(!quoted, "Characters after a quoted field");
+                field.push(c);
+                chars.next();
+            }
+            record.push(if field.is_empty() && !quoted {
+                None
+            } else {
+                Some(field)
+            });
+
+            match chars.next() {
+                Some('\r') => {
+                    assert_eq!(chars.next(), Some('\n'));
+                    break;
+                }
+                Some(c) => assert_eq!(c, delimiter),
+                None => panic!("Record without a line break"),
+            }
+        }
+        records.push(record);
+    }
+
+    records
+}
+
+fn some(value: &str) -> Option<String> {
+    Some(String::from(value))
+}
+
+#[test]
+fn csv_tables_have_headers() {
+    let directory = convert("csv", "sample/osm.xml", &["--format", "csv"]);
+
+    let nodes = parse_csv(
+        &fs::read_to_string(directory.join("nodes.csv")).unwrap(),
+        ',',
+    );
+    assert_eq!(
+        nodes[0],
+        [
+            "id",
+            "lat",
+            "lng",
+            "version",
+            "changeset",
+            "user",
+            "uid",
+            "visible",
+            "timestamp"
+        ]
+        .iter()
+        .map(|column| some(column))
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(nodes.len(), 5);
+    assert_eq!(nodes[1][0], some("298884269"));
+    assert_eq!(nodes[1][7], some("true"));
+
+    for table in TABLES.iter() {
+        assert!(directory.join(format!("{}.csv", table)).exists());
+    }
+
+    // Null values and empty strings are different.
+    let members = parse_csv(
+        &fs::read_to_string(directory.join("relation_members.csv")).unwrap(),
+        ',',
+    );
+    assert_eq!(
+        members[3],
+        vec![
+            some("56688"),
+            None,
+            some("4579143"),
+            None,
+            some(""),
+            some("2")
+        ]
+    );
+}
+
+#[test]
+fn csv_and_tsv_fields_are_quoted() {
+    let hostile = "comma, tab\t\"quote\" new\nline\r\n end";
+    let mut pbf = PbfWriter::new();
+    pbf.node(1, 10, 20, hostile, &[]);
+    pbf.node(2, 10, 20, "", &[]);
+    let input = output_directory("csv-quote-input").join("input.osm.pbf");
+    pbf.write(&input);
+
+    for (format, delimiter) in [("csv", ','), ("tsv", '\t')].iter() {
+        let directory = convert(
+            &format!("{}-quote", format),
+            input.to_str().unwrap(),
+            &["--format", format],
+        );
+        let text = fs::read_to_string(directory.join(format!("nodes.{}", format))).unwrap();
+        let nodes = parse_csv(&text, *delimiter);
+
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(nodes[1][5], some(hostile));
+        assert_eq!(nodes[2][5], some(""));
+    }
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::pbf::PbfWriter;
+use common::*;
+use rusqlite::Connection;
+
+fn count(connection: &Connection, table: &str) -> i64 {
+    connection
+        .query_row(&format!("SELECT count(*) FROM {}", table), [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+}
+
+#[test]
+fn sqlite_database_is_written() {
+    let directory = output_directory("database");
+    let database = directory.join("osm.db");
+    let database = database.to_str().unwrap();
+    run(&["-i", "sample/osm.xml", "--database", database]);
+
+    // Only the database is left after the command exits.
+    assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 1);
+
+    let connection = Connection::open(database).unwrap();
+    let counts: Vec<i64> = TABLES
+        .iter()
+        .map(|table| count(&connection, table))
+        .collect();
+    assert_eq!(counts, vec![4, 1, 3, 1, 4, 8, 10]);
+
+    let (user, visible, lat): (String, bool, f64) = connection
+        .query_row(
+            "SELECT user, visible, lat FROM nodes WHERE id = 298884269",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap();
+    assert_eq!(user, "SvenHRO");
+    assert!(visible);
+    assert_eq!(lat, 54.0901746);
+
+    let integrity: String = connection
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(integrity, "ok");
+    drop(connection);
+
+    // Existing tables are reused and duplicate rows are ignored.
+    run(&["-i", "sample/osm.xml", "--database", database]);
+    let connection = Connection::open(database).unwrap();
+    assert_eq!(count(&connection, "nodes"), 4);
+}
+
+#[test]
+fn sqlite_database_stores_strings_unchanged() {
+    let hostile = "quote ' double \" nul \0 cr \r lf \n back \\ emoji \u{1F600}";
+    let mut pbf = PbfWriter::new();
+    pbf.node(1, 10, 20, hostile, &[("name", hostile)]);
+    let directory = output_directory("database-escape");
+    let input = directory.join("input.osm.pbf");
+    pbf.write(&input);
+    let database = directory.join("osm.db");
+
+    run(&[
+        "-i",
+        input.to_str().unwrap(),
+        "--database",
+        database.to_str().unwrap(),
+    ]);
+
+    let connection = Connection::open(&database).unwrap();
+    let user: String = connection
+        .query_row("SELECT user FROM nodes", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(user, hostile);
+}
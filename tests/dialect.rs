@@ -0,0 +1,69 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn postgres_dialect() {
+    let directory = convert(
+        "dialect-postgres",
+        "sample/osm.xml",
+        &["--dialect", "postgres"],
+    );
+
+    let nodes = read_table(&directory, "nodes");
+    assert!(nodes.contains("\"user\" TEXT"));
+    assert!(nodes.contains("visible BOOLEAN"));
+    assert!(nodes.contains(",'SvenHRO',46882,TRUE,'2008-09-21T21:37:45Z')"));
+    assert!(nodes.trim_end().ends_with(" ON CONFLICT DO NOTHING;"));
+
+    let ref_tags = read_table(&directory, "ref_tags");
+    assert!(ref_tags.contains("rt_id BIGSERIAL"));
+    assert!(!ref_tags.contains("AUTO_INCREMENT"));
+    assert!(!ref_tags.contains("IGNORE"));
+    assert!(ref_tags.contains(
+        "ALTER TABLE ref_tags ADD CONSTRAINT ref_tags_tags_fk FOREIGN KEY(tag_id) REFERENCES tags(id) NOT VALID;"
+    ));
+}
+
+#[test]
+fn postgres_dialect_without_ignore() {
+    let directory = convert(
+        "dialect-postgres-no-ignore",
+        "sample/osm.xml",
+        &["--dialect", "postgres", "--no-ignore"],
+    );
+
+    assert!(!read_table(&directory, "nodes").contains("ON CONFLICT"));
+    assert!(!read_table(&directory, "ref_tags").contains("NOT VALID"));
+}
+
+#[test]
+fn sqlite_dialect() {
+    let directory = convert("dialect-sqlite", "sample/osm.xml", &["--dialect", "sqlite"]);
+
+    let ref_tags = read_table(&directory, "ref_tags");
+    assert!(ref_tags.contains("rt_id INTEGER PRIMARY KEY AUTOINCREMENT"));
+    assert!(!ref_tags.contains("ref_tags_pk"));
+    assert!(ref_tags.contains("INSERT OR IGNORE INTO ref_tags"));
+
+    let nodes = read_table(&directory, "nodes");
+    assert!(nodes.contains("lat REAL"));
+    assert!(nodes.contains(",'SvenHRO',46882,1,'2008-09-21T21:37:45Z')"));
+}
+
+#[test]
+fn sqlite_dialect_limits_rows_per_query() {
+    let nodes: String = (1..=1200).map(|id| node_xml(id, "1.0", "2.0")).collect();
+    let input = write_xml("dialect-sqlite-rows-input", &nodes);
+    let input = input.to_str().unwrap();
+
+    let sqlite = convert(
+        "dialect-sqlite-rows",
+        input,
+        &["--dialect", "sqlite", "-r", "1000"],
+    );
+    assert_eq!(read_table(&sqlite, "nodes").matches("INSERT").count(), 3);
+
+    let mysql = convert("dialect-mysql-rows", input, &["-r", "1000"]);
+    assert_eq!(read_table(&mysql, "nodes").matches("INSERT").count(), 2);
+}
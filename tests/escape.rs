@@ -0,0 +1,307 @@
+mod common;
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+use common::pbf::PbfWriter;
+use common::sql::parse_rows;
+use common::*;
+use rusqlite::Connection;
+
+const HOSTILE: [&str; 9] = [
+    "single ' and double \" quotes",
+    "back\\slash \\' \\\\ end\\",
+    "new\nline\r\nand\ttab",
+    "controls \u{1} \u{1a} \u{1b} \u{7f}",
+    "nul \0 byte",
+    "non bmp 😀 𝄞 𠜎",
+    "'); DROP TABLE nodes; --",
+    "\"); DROP TABLE nodes; --",
+    "",
+];
+
+/// Writes a PBF file where every hostile string is a user name, a tag key,
+/// a tag value and a relation member role.
+fn hostile_input(name: &str) -> String {
+    let mut pbf = PbfWriter::new();
+    for (i, hostile) in HOSTILE.iter().enumerate() {
+        let id = i as i64 + 1;
+        let tags = [(*hostile, *hostile)];
+        pbf.node(id, 10, 20, hostile, &tags);
+        pbf.way(id, hostile, &[id], &tags);
+        pbf.relation(id, hostile, &[("node", id, hostile)], &tags);
+    }
+
+    let path = output_directory(name).join("hostile.osm.pbf");
+    pbf.write(&path);
+    String::from(path.to_str().unwrap())
+}
+
+fn expected(hostile: &str, dialect: &str) -> String {
+    if dialect == "postgres" {
+        hostile.replace('\0', "\u{FFFD}")
+    } else {
+        String::from(hostile)
+    }
+}
+
+/// String columns as (table, column, order) with the hostile strings in
+/// every row.
+const COLUMNS: [(&str, &str, &str); 5] = [
+    ("nodes", "user", "id"),
+    ("ways", "user", "id"),
+    ("relations", "user", "id"),
+    ("tags", "name", "id"),
+    ("relation_members", "role", "relation_id, sequence_id"),
+];
+
+/// Checks the values that a database stored from the output files. The
+/// query returns the values of a table, column and order of `COLUMNS`.
+fn assert_stored_values(dialect: &str, mut query: impl FnMut(&str, &str, &str) -> Vec<String>) {
+    let expected: Vec<String> = HOSTILE.iter().map(|h| expected(h, dialect)).collect();
+
+    for (table, column, order) in COLUMNS.iter() {
+        assert_eq!(
+            query(table, column, order),
+            expected,
+            "{} {}.{}",
+            dialect,
+            table,
+            column
+        );
+    }
+    // Tag values of the nodes, ways and relations.
+    assert_eq!(
+        query("ref_tags", "value", "rt_id"),
+        [&expected[..], &expected[..], &expected[..]].concat(),
+        "{} ref_tags.value",
+        dialect
+    );
+}
+
+/// Values of a string column for every row of the table.
+fn column(directory: &Path, table: &str, index: usize, dialect: &str) -> Vec<String> {
+    parse_rows(&read_table(directory, table), dialect)
+        .into_iter()
+        .map(|row| row[index].clone().unwrap())
+        .collect()
+}
+
+#[test]
+fn hostile_strings_round_trip_in_every_dialect() {
+    let input = hostile_input("escape-rows-input");
+
+    for dialect in ["mysql", "postgres", "sqlite"].iter() {
+        let directory = convert(
+            &format!("escape-rows-{}", dialect),
+            &input,
+            &["--dialect", dialect],
+        );
+        let expected: Vec<String> = HOSTILE.iter().map(|h| expected(h, dialect)).collect();
+
+        // The user column of the tables with the main information.
+        assert_eq!(
+            column(&directory, "nodes", 5, dialect),
+            expected,
+            "{}",
+            dialect
+        );
+        assert_eq!(
+            column(&directory, "ways", 3, dialect),
+            expected,
+            "{}",
+            dialect
+        );
+        assert_eq!(
+            column(&directory, "relations", 3, dialect),
+            expected,
+            "{}",
+            dialect
+        );
+        assert_eq!(
+            column(&directory, "tags", 1, dialect),
+            expected,
+            "{}",
+            dialect
+        );
+        assert_eq!(
+            column(&directory, "relation_members", 4, dialect),
+            expected,
+            "{}",
+            dialect
+        );
+
+        // Tag values of the nodes, ways and relations.
+        assert_eq!(
+            column(&directory, "ref_tags", 4, dialect),
+            [&expected[..], &expected[..], &expected[..]].concat(),
+            "{}",
+            dialect
+        );
+    }
+}
+
+/// Runs the SQL files of the sqlite dialect in an in-memory database.
+fn load_sqlite(directory: &Path) -> Connection {
+    let connection = Connection::open_in_memory().unwrap();
+    for table in TABLES.iter() {
+        connection
+            .execute_batch(&read_table(directory, table))
+            .unwrap_or_else(|e| panic!("Can not load {}: {}", table, e));
+    }
+    connection
+}
+
+#[test]
+fn hostile_strings_are_stored_by_sqlite() {
+    let directory = convert(
+        "escape-sqlite",
+        &hostile_input("escape-sqlite-input"),
+        &["--dialect", "sqlite"],
+    );
+    let connection = load_sqlite(&directory);
+
+    assert_stored_values("sqlite", |table, column, order| {
+        query_column(
+            &connection,
+            &format!("SELECT \"{}\" FROM {} ORDER BY {}", column, table, order),
+        )
+    });
+}
+
+/// Command line client of a database server from an environment variable
+/// like `psql -h localhost -U postgres -d test`. The tests of the servers
+/// are ignored by default and run with `cargo test -- --ignored`.
+fn client(variable: &str) -> Vec<String> {
+    env::var(variable)
+        .unwrap_or_else(|_| panic!("Set {} to the command of the client.", variable))
+        .split_whitespace()
+        .map(String::from)
+        .collect()
+}
+
+/// Runs the client with the arguments and returns the standard output.
+fn run_client(client: &[String], args: &[&str]) -> String {
+    let output = Command::new(&client[0])
+        .args(&client[1..])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{:?} {:?} failed: {}",
+        client,
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Decodes a hex encoded value on every line. Values are read as hex, so
+/// they are not changed by the output format of the client.
+fn decode_hex(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(|line| {
+            let bytes = (0..line.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&line[i..i + 2], 16).unwrap())
+                .collect();
+            String::from_utf8(bytes).unwrap()
+        })
+        .collect()
+}
+
+const DROP_TABLES: &str =
+    "DROP TABLE IF EXISTS ref_tags, tags, relation_members, relations, way_nodes, ways, nodes";
+
+#[test]
+#[ignore]
+fn hostile_strings_are_stored_by_postgres() {
+    let client = client("OSM_TO_SQL_PSQL");
+    let directory = convert(
+        "escape-postgres",
+        &hostile_input("escape-postgres-input"),
+        &["--dialect", "postgres"],
+    );
+
+    run_client(&client, &["-q", "-c", &format!("{} CASCADE", DROP_TABLES)]);
+    for table in TABLES.iter() {
+        let path = directory.join(format!("{}.sql", table));
+        run_client(
+            &client,
+            &["-q", "-v", "ON_ERROR_STOP=1", "-f", path.to_str().unwrap()],
+        );
+    }
+
+    assert_stored_values("postgres", |table, column, order| {
+        decode_hex(&run_client(
+            &client,
+            &[
+                "-At",
+                "-c",
+                &format!(
+                    "SELECT encode(convert_to(\"{}\", 'UTF8'), 'hex') FROM {} ORDER BY {}",
+                    column, table, order
+                ),
+            ],
+        ))
+    });
+}
+
+#[test]
+#[ignore]
+fn hostile_strings_are_stored_by_mysql() {
+    let client = client("OSM_TO_SQL_MYSQL");
+    let directory = convert(
+        "escape-mysql",
+        &hostile_input("escape-mysql-input"),
+        &["--dialect", "mysql"],
+    );
+
+    run_client(
+        &client,
+        &[
+            "-e",
+            &format!("SET FOREIGN_KEY_CHECKS = 0; {}", DROP_TABLES),
+        ],
+    );
+    for table in TABLES.iter() {
+        let path = directory.join(format!("{}.sql", table));
+        run_client(
+            &client,
+            &["-e", &format!("source {}", path.to_str().unwrap())],
+        );
+    }
+
+    assert_stored_values("mysql", |table, column, order| {
+        decode_hex(&run_client(
+            &client,
+            &[
+                "-N",
+                "-B",
+                "-e",
+                &format!("SELECT HEX(`{}`) FROM {} ORDER BY {}", column, table, order),
+            ],
+        ))
+    });
+}
+
+#[test]
+fn xml_entities_are_unescaped() {
+    let input = write_xml(
+        "escape-xml-input",
+        " <node id=\"1\" lat=\"1\" lon=\"2\" user=\"a &amp; b &quot;c&quot; &lt;d&gt; &#10;&#x1F600;\" uid=\"1\" visible=\"true\" version=\"1\" changeset=\"1\" timestamp=\"2020-01-01T00:00:00Z\"/>\n",
+    );
+    let directory = convert(
+        "escape-xml",
+        input.to_str().unwrap(),
+        &["--dialect", "sqlite"],
+    );
+
+    assert_eq!(
+        query_column(&load_sqlite(&directory), "SELECT \"user\" FROM nodes"),
+        vec!["a & b \"c\" <d> \n😀"]
+    );
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use common::*;
+use rusqlite::Connection;
+
+/// A cafe, a bank and untagged nodes. Way 10 is a highway, way 11 is a
+/// building and the untagged way 12 is a member of the route 20. The route
+/// has the relation 21 referring to the bank.
+fn input(name: &str) -> String {
+    let mut xml = tagged_node_xml(1, "1", "1", &[("amenity", "cafe")])
+        + &tagged_node_xml(2, "1", "1", &[("amenity", "bank")]);
+    for id in 3..=6 {
+        xml += &node_xml(id, "1", "1");
+    }
+    xml += &way_xml(10, &[3, 1], &[("highway", "primary")]);
+    xml += &way_xml(11, &[4, 2], &[("building", "yes")]);
+    xml += &way_xml(12, &[6], &[]);
+    xml += &relation_xml(
+        20,
+        &[("way", 12, ""), ("node", 5, "stop"), ("relation", 21, "")],
+        &[("type", "route")],
+    );
+    xml += &relation_xml(21, &[("node", 2, "")], &[("type", "other")]);
+
+    String::from(write_xml(name, &xml).to_str().unwrap())
+}
+
+/// Converts the input with the filter options and checks that all foreign
+/// keys are referring to written rows.
+fn filter(name: &str, args: &[&str]) -> Connection {
+    filter_input(name, &input(&format!("{}-input", name)), args)
+}
+
+fn filter_input(name: &str, input: &str, args: &[&str]) -> Connection {
+    let directory = output_directory(name);
+    let database = directory.join("osm.db");
+    let mut all_args = vec!["-i", input, "--database", database.to_str().unwrap()];
+    all_args.extend_from_slice(args);
+    run(&all_args);
+
+    let connection = Connection::open(&database).unwrap();
+    assert!(query_column(&connection, "PRAGMA foreign_key_check").is_empty());
+    connection
+}
+
+#[test]
+fn kept_elements_are_written_with_their_references() {
+    let connection = filter(
+        "filter-keep",
+        &["--keep", "w/highway n/amenity=cafe,restaurant r/type=route"],
+    );
+
+    // The bank is a member of the sub relation 21.
+    assert_eq!(
+        query_column(&connection, "SELECT id FROM nodes"),
+        ["1", "2", "3", "5", "6"]
+    );
+    assert_eq!(
+        query_column(&connection, "SELECT id FROM ways"),
+        ["10", "12"]
+    );
+    assert_eq!(
+        query_column(&connection, "SELECT id FROM relations"),
+        ["20", "21"]
+    );
+    assert_eq!(
+        query_column(
+            &connection,
+            "SELECT relation_id || ':' || coalesce(node_id, way_id, sub_relation_id) FROM relation_members"
+        ),
+        ["20:12", "20:5", "20:21", "21:2"]
+    );
+    assert_eq!(
+        query_column(
+            &connection,
+            "SELECT coalesce(node_id, way_id, relation_id) || ':' || value FROM ref_tags"
+        ),
+        ["1:cafe", "2:bank", "10:primary", "20:route", "21:other"]
+    );
+}
+
+#[test]
+fn referenced_elements_are_not_dropped() {
+    let connection = filter(
+        "filter-drop",
+        &["--drop", "w/building", "--drop", "n/amen*=bank"],
+    );
+
+    // The bank is a member of the relation 21.
+    assert_eq!(
+        query_column(&connection, "SELECT id FROM nodes"),
+        ["1", "2", "3", "4", "5", "6"]
+    );
+    assert_eq!(
+        query_column(&connection, "SELECT id FROM ways"),
+        ["10", "12"]
+    );
+    assert_eq!(
+        query_column(&connection, "SELECT id FROM relations"),
+        ["20", "21"]
+    );
+}
+
+#[test]
+fn filter_is_applied_in_the_region() {
+    let connection = filter(
+        "filter-bbox",
+        &["--keep", "n/amenity", "--bbox", "0,0,0.5,0.5"],
+    );
+    assert!(query_column(&connection, "SELECT id FROM nodes").is_empty());
+}
+
+#[test]
+fn sub_relations_are_resolved_at_any_depth() {
+    // The route 30 has the relation 31, which has the relation 32 before it
+    // in the input. The relations 31 and 32 are members of each other. The
+    // relation 33 is not referred to.
+    let mut xml = String::new();
+    for id in 1..=4 {
+        xml += &node_xml(id, "1", "1");
+    }
+    xml += &way_xml(10, &[1], &[]);
+    xml += &way_xml(11, &[2], &[]);
+    xml += &relation_xml(32, &[("way", 11, ""), ("relation", 31, "")], &[]);
+    xml += &relation_xml(30, &[("relation", 31, "")], &[("type", "route")]);
+    xml += &relation_xml(31, &[("node", 3, ""), ("relation", 32, "")], &[]);
+    xml += &relation_xml(33, &[("node", 4, ""), ("way", 10, "")], &[]);
+    let input = write_xml("filter-nested-input", &xml);
+
+    let connection = filter_input(
+        "filter-nested",
+        input.to_str().unwrap(),
+        &["--keep", "r/type=route"],
+    );
+
+    assert_eq!(
+        query_column(&connection, "SELECT id FROM nodes"),
+        ["2", "3"]
+    );
+    assert_eq!(query_column(&connection, "SELECT id FROM ways"), ["11"]);
+    assert_eq!(
+        query_column(&connection, "SELECT id FROM relations"),
+        ["30", "31", "32"]
+    );
+    assert_eq!(
+        query_column(
+            &connection,
+            "SELECT relation_id || ':' || coalesce(node_id, way_id, sub_relation_id) FROM relation_members ORDER BY relation_id, sequence_id"
+        ),
+        ["30:31", "31:3", "31:32", "32:11", "32:31"]
+    );
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::*;
+use rusqlite::Connection;
+
+const SQUARE: [i64; 5] = [1, 2, 3, 4, 1];
+
+/// A unit square at the null island, a far node and ways on them. The node
+/// 99 is missing.
+fn input(name: &str) -> String {
+    let xml = node_xml(1, "0", "0")
+        + &node_xml(2, "0", "1")
+        + &node_xml(3, "1", "1")
+        + &node_xml(4, "1", "0")
+        + &node_xml(100000, "-1.5", "2.25")
+        + &way_xml(10, &SQUARE, &[("building", "yes")])
+        + &way_xml(11, &SQUARE, &[("highway", "service")])
+        + &way_xml(12, &SQUARE, &[("building", "yes"), ("area", "no")])
+        + &way_xml(13, &[3, 100000], &[])
+        + &way_xml(14, &[1, 99], &[])
+        + &way_xml(15, &[2, 99, 3], &[]);
+
+    String::from(write_xml(name, &xml).to_str().unwrap())
+}
+
+fn geometries(name: &str, args: &[&str]) -> Vec<(i64, Option<String>)> {
+    let input = input(&format!("{}-input", name));
+    let database = output_directory(name).join("osm.db");
+    let mut all_args = vec!["-i", &input, "--database", database.to_str().unwrap()];
+    all_args.extend_from_slice(args);
+    run(&all_args);
+
+    let connection = Connection::open(&database).unwrap();
+    let mut statement = connection
+        .prepare("SELECT id, geom FROM ways ORDER BY id")
+        .unwrap();
+    let rows = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap();
+    rows.map(Result::unwrap).collect()
+}
+
+fn expected_wkt() -> Vec<(i64, Option<String>)> {
+    vec![
+        (10, Some(String::from("POLYGON((0 0,1 0,1 1,0 1,0 0))"))),
+        (11, Some(String::from("LINESTRING(0 0,1 0,1 1,0 1,0 0)"))),
+        (12, Some(String::from("LINESTRING(0 0,1 0,1 1,0 1,0 0)"))),
+        (13, Some(String::from("LINESTRING(1 1,2.25 -1.5)"))),
+        (14, None),
+        (15, Some(String::from("LINESTRING(1 0,1 1)"))),
+    ]
+}
+
+#[test]
+fn ways_are_written_as_wkt() {
+    assert_eq!(
+        geometries("geometry-memory", &["--geometry", "wkt"]),
+        expected_wkt()
+    );
+}
+
+#[test]
+fn disk_index_has_the_same_locations() {
+    assert_eq!(
+        geometries(
+            "geometry-disk",
+            &[
+                "--geometry",
+                "wkt",
+                "--node-index",
+                "disk",
+                "--types",
+                "ways"
+            ]
+        ),
+        expected_wkt()
+    );
+}
+
+#[test]
+fn ways_are_written_as_wkb() {
+    let rows = geometries("geometry-wkb", &["--geometry", "wkb"]);
+
+    let mut wkb = vec![1, 2, 0, 0, 0, 2, 0, 0, 0];
+    for coordinate in [1.0f64, 1.0, 2.25, -1.5] {
+        wkb.extend_from_slice(&coordinate.to_le_bytes());
+    }
+    let hex: String = wkb.iter().map(|byte| format!("{:02X}", byte)).collect();
+    assert_eq!(rows[3], (13, Some(hex)));
+    // Polygon with one ring of five points.
+    assert!(rows[0]
+        .1
+        .as_deref()
+        .unwrap()
+        .starts_with("01030000000100000005000000"));
+}
+
+#[test]
+fn area_tags_are_configurable() {
+    let rows = geometries(
+        "geometry-area-tags",
+        &[
+            "--geometry",
+            "wkt",
+            "--area-tags",
+            "highway=service,pedestrian",
+        ],
+    );
+
+    assert_eq!(
+        rows[0].1.as_deref(),
+        Some("LINESTRING(0 0,1 0,1 1,0 1,0 0)")
+    );
+    assert_eq!(rows[1].1.as_deref(), Some("POLYGON((0 0,1 0,1 1,0 1,0 0))"));
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::pbf::PbfWriter;
+use common::sql::parse_rows;
+use common::*;
+use std::fs;
+use std::path::Path;
+
+/// Compares the output with the expected files in `sample/output`. The
+/// expected files should only be regenerated after checking the changes.
+fn assert_golden(directory: &Path) {
+    for table in TABLES.iter() {
+        let expected = fs::read_to_string(format!("sample/output/{}.sql", table)).unwrap();
+        assert_eq!(
+            read_table(directory, table),
+            expected,
+            "{}.sql is not matching the golden file",
+            table
+        );
+    }
+}
+
+#[test]
+fn golden_xml_output() {
+    assert_golden(&convert("golden-xml", "sample/osm.xml", &[]));
+}
+
+#[test]
+fn golden_pbf_output() {
+    assert_golden(&convert("golden-pbf", "sample/osm.pbf", &[]));
+}
+
+#[test]
+fn coordinates_match_the_input() {
+    let xml = fs::read_to_string("sample/osm.xml").unwrap();
+    let attribute = |element: &str, name: &str| -> String {
+        let start = element.find(&format!(" {}=\"", name)).unwrap() + name.len() + 3;
+        String::from(&element[start..start + element[start..].find('"').unwrap()])
+    };
+    let mut expected: Vec<Vec<Option<String>>> = xml
+        .split("<node")
+        .skip(1)
+        .map(|element| {
+            vec![
+                Some(attribute(element, "id")),
+                Some(attribute(element, "lat")),
+                Some(attribute(element, "lon")),
+            ]
+        })
+        .collect();
+    expected.sort();
+
+    for input in ["sample/osm.xml", "sample/osm.pbf"].iter() {
+        for dialect in ["mysql", "postgres", "sqlite"].iter() {
+            let directory = convert("golden-coordinates", input, &["--dialect", dialect]);
+            let mut nodes: Vec<Vec<Option<String>>> =
+                parse_rows(&read_table(&directory, "nodes"), dialect)
+                    .into_iter()
+                    .map(|row| row[..3].to_vec())
+                    .collect();
+            nodes.sort();
+            assert_eq!(nodes, expected, "{} in {}", input, dialect);
+        }
+    }
+}
+
+#[test]
+fn extreme_coordinates() {
+    let coordinates = [
+        (1, "90", "180", 900_000_000, 1_800_000_000),
+        (2, "-90", "-180", -900_000_000, -1_800_000_000),
+        (3, "89.9999999", "179.9999999", 899_999_999, 1_799_999_999),
+        (4, "-0.0000001", "0.0000001", -1, 1),
+        (5, "0", "-12.5", 0, -125_000_000),
+    ];
+    let mut elements = String::new();
+    let mut pbf = PbfWriter::new();
+    for (id, lat, lon, lat_units, lon_units) in coordinates.iter() {
+        elements += &node_xml(*id, lat, lon);
+        pbf.node(*id, *lat_units, *lon_units, "u", &[]);
+    }
+    let xml_input = write_xml("extreme-xml-input", &elements);
+    let pbf_input = output_directory("extreme-pbf-input").join("input.osm.pbf");
+    pbf.write(&pbf_input);
+
+    for input in [xml_input, pbf_input].iter() {
+        let directory = convert("extreme", input.to_str().unwrap(), &[]);
+        let nodes = parse_rows(&read_table(&directory, "nodes"), "mysql");
+        for ((_, lat, lon, _, _), row) in coordinates.iter().zip(nodes.iter()) {
+            assert_eq!(row[1].as_deref(), Some(*lat));
+            assert_eq!(row[2].as_deref(), Some(*lon));
+        }
+    }
+}
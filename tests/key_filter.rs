@@ -0,0 +1,93 @@
+mod common;
+
+use common::pbf::PbfWriter;
+use common::sql::parse_rows;
+use common::*;
+use rusqlite::Connection;
+
+const TAGS: [(&str, &str); 5] = [
+    ("name", "Main Street"),
+    ("highway", "residential"),
+    ("tiger:county", "Travis, TX"),
+    ("tiger:cfcc", "A41"),
+    ("source", "survey"),
+];
+
+fn input(name: &str) -> String {
+    let mut pbf = PbfWriter::new();
+    pbf.node(1, 10, 20, "u", &TAGS);
+    pbf.way(2, "u", &[1], &TAGS);
+    pbf.relation(3, "u", &[("way", 2, "")], &TAGS);
+
+    let path = output_directory(name).join("input.osm.pbf");
+    pbf.write(&path);
+    String::from(path.to_str().unwrap())
+}
+
+#[test]
+fn dropped_keys_are_not_written() {
+    let input = input("key-filter-drop-input");
+    let directory = output_directory("key-filter-drop");
+    let database = directory.join("osm.db");
+    run(&[
+        "-i",
+        &input,
+        "--database",
+        database.to_str().unwrap(),
+        "--drop-tags",
+        "tiger:*,source*",
+    ]);
+
+    let connection = Connection::open(&database).unwrap();
+    assert_eq!(
+        query_column(&connection, "SELECT name FROM tags ORDER BY name"),
+        vec!["highway", "name"]
+    );
+    for column in ["node_id", "way_id", "relation_id"] {
+        assert_eq!(
+            query_column(
+                &connection,
+                &format!(
+                    "SELECT t.name FROM ref_tags r JOIN tags t ON t.id = r.tag_id \
+                     WHERE r.{} IS NOT NULL ORDER BY t.name",
+                    column
+                )
+            ),
+            vec!["highway", "name"]
+        );
+    }
+}
+
+#[test]
+fn only_kept_keys_are_written() {
+    let input = input("key-filter-keep-input");
+    let directory = convert(
+        "key-filter-keep",
+        &input,
+        &["--keep-tags", "name,tiger:*", "--drop-tags", "tiger:cfcc"],
+    );
+
+    let tags = parse_rows(&read_table(&directory, "tags"), "mysql");
+    let names: Vec<&str> = tags.iter().map(|row| row[1].as_deref().unwrap()).collect();
+    assert_eq!(names, vec!["name", "tiger:county"]);
+    assert_eq!(
+        parse_rows(&read_table(&directory, "ref_tags"), "mysql").len(),
+        6
+    );
+}
+
+#[test]
+fn dropped_keys_are_not_encoded() {
+    let input = input("key-filter-json-input");
+    let directory = convert(
+        "key-filter-json",
+        &input,
+        &["--tags", "json", "--drop-tags", "tiger:*,source"],
+    );
+
+    let nodes = parse_rows(&read_table(&directory, "nodes"), "mysql");
+    assert_eq!(
+        nodes[0].last().unwrap().as_deref(),
+        Some("{\"name\":\"Main Street\",\"highway\":\"residential\"}")
+    );
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use common::*;
+use rusqlite::Connection;
+
+/// A square with a hole and an island in the hole, and a second square.
+/// The outer ring of the first square is split into two ways. The hole is
+/// counterclockwise and the second square is clockwise. The relation 201
+/// has an open ring and the boundary 203 is missing a way. The relation
+/// 204 has the second square twice.
+fn input(name: &str) -> String {
+    let nodes = [
+        (1, "0", "0"),
+        (2, "0", "10"),
+        (3, "10", "10"),
+        (4, "10", "0"),
+        (5, "2", "2"),
+        (6, "2", "4"),
+        (7, "4", "4"),
+        (8, "4", "2"),
+        (9, "2.5", "2.5"),
+        (10, "2.5", "3.5"),
+        (11, "3.5", "3.5"),
+        (12, "3.5", "2.5"),
+        (13, "0", "20"),
+        (14, "0", "22"),
+        (15, "2", "22"),
+        (16, "2", "20"),
+    ];
+    let mut xml: String = nodes
+        .iter()
+        .map(|(id, lat, lon)| node_xml(*id, lat, lon))
+        .collect();
+    xml += &way_xml(100, &[1, 2, 3], &[]);
+    xml += &way_xml(101, &[1, 4, 3], &[]);
+    xml += &way_xml(102, &[5, 6, 7, 8, 5], &[]);
+    xml += &way_xml(103, &[9, 10, 11, 12, 9], &[]);
+    xml += &way_xml(104, &[13, 16, 15, 14, 13], &[]);
+    let type_tag = |relation_type| [("type", relation_type)];
+    xml += &relation_xml(
+        200,
+        &[
+            ("way", 100, "outer"),
+            ("way", 101, "outer"),
+            ("way", 102, "inner"),
+            ("way", 103, "outer"),
+            ("way", 104, ""),
+            ("node", 1, "label"),
+        ],
+        &type_tag("multipolygon"),
+    );
+    xml += &relation_xml(201, &[("way", 100, "outer")], &type_tag("multipolygon"));
+    xml += &relation_xml(202, &[("way", 100, "")], &type_tag("route"));
+    xml += &relation_xml(
+        203,
+        &[("way", 104, "outer"), ("way", 999, "outer")],
+        &type_tag("boundary"),
+    );
+    xml += &relation_xml(
+        204,
+        &[("way", 104, "outer"), ("way", 104, "outer")],
+        &type_tag("multipolygon"),
+    );
+
+    String::from(write_xml(name, &xml).to_str().unwrap())
+}
+
+/// Converts the input and returns the relation geometries with the
+/// standard error.
+fn geometries(name: &str, args: &[&str]) -> (Vec<(i64, Option<String>)>, String) {
+    let input = input(&format!("{}-input", name));
+    let database = output_directory(name).join("osm.db");
+    let mut all_args = vec!["-i", &input, "--database", database.to_str().unwrap()];
+    all_args.extend_from_slice(args);
+    let output = run(&all_args);
+
+    let connection = Connection::open(&database).unwrap();
+    let mut statement = connection
+        .prepare("SELECT id, geom FROM relations ORDER BY id")
+        .unwrap();
+    let rows = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap();
+    (
+        rows.map(Result::unwrap).collect(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+const MULTIPOLYGON: &str = "MULTIPOLYGON(\
+    ((0 0,10 0,10 10,0 10,0 0),(2 2,2 4,4 4,4 2,2 2)),\
+    ((2.5 2.5,3.5 2.5,3.5 3.5,2.5 3.5,2.5 2.5)),\
+    ((20 0,22 0,22 2,20 2,20 0)))";
+
+const SQUARE: &str = "MULTIPOLYGON(((20 0,22 0,22 2,20 2,20 0)))";
+
+#[test]
+fn rings_are_assembled_into_multipolygons() {
+    let (rows, stderr) = geometries("multipolygon-wkt", &["--geometry", "wkt"]);
+
+    assert_eq!(
+        rows,
+        vec![
+            (200, Some(String::from(MULTIPOLYGON))),
+            (201, None),
+            (202, None),
+            (203, None),
+            (204, Some(String::from(SQUARE))),
+        ]
+    );
+    assert!(stderr.contains(
+        "The relation 201 is not a valid area: the outer ring is not closed at the node 3."
+    ));
+    assert!(stderr
+        .contains("The relation 203 is not a valid area: the member way 999 is not in the input."));
+    assert!(!stderr.contains("202"));
+}
+
+#[test]
+fn ways_are_not_needed_in_the_output() {
+    let (rows, _) = geometries(
+        "multipolygon-types",
+        &["--geometry", "wkt", "--types", "relations"],
+    );
+
+    assert_eq!(rows[0], (200, Some(String::from(MULTIPOLYGON))));
+}
+
+#[test]
+fn multipolygons_are_written_as_wkb() {
+    let (rows, _) = geometries(
+        "multipolygon-wkb",
+        &["--geometry", "wkb", "--node-index", "disk"],
+    );
+
+    // Three polygons. The first one has two rings of five points.
+    let wkb = rows[0].1.as_deref().unwrap();
+    assert!(wkb.starts_with("010600000003000000010300000002000000050000000000000000000000"));
+    // Every point has two doubles and every polygon a header of 9 bytes,
+    // ring counts and point counts.
+    assert_eq!(wkb.len(), 2 * (9 + 3 * 9 + 4 * 4 + 20 * 16));
+}
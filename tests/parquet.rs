@@ -0,0 +1,93 @@
+mod common;
+
+use common::*;
+use parquet::basic::{LogicalType, Type as PhysicalType};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::Field;
+use std::fs::File;
+use std::path::Path;
+
+fn open(directory: &Path, table: &str) -> SerializedFileReader<File> {
+    let file = File::open(directory.join(format!("{}.parquet", table))).unwrap();
+    SerializedFileReader::new(file).unwrap()
+}
+
+fn fields(reader: &SerializedFileReader<File>) -> Vec<Vec<Field>> {
+    reader
+        .get_row_iter(None)
+        .unwrap()
+        .map(|row| {
+            row.unwrap()
+                .get_column_iter()
+                .map(|(_, field)| field.clone())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn parquet_schema_and_row_groups() {
+    let directory = convert(
+        "parquet",
+        "sample/osm.xml",
+        &["--format", "parquet", "--row-group-size", "3"],
+    );
+
+    for table in TABLES.iter() {
+        assert!(directory.join(format!("{}.parquet", table)).exists());
+    }
+
+    let nodes = open(&directory, "nodes");
+    let metadata = nodes.metadata();
+    assert_eq!(metadata.file_metadata().num_rows(), 4);
+    assert_eq!(metadata.num_row_groups(), 2);
+
+    let schema = metadata.file_metadata().schema_descr();
+    let types: Vec<(&str, PhysicalType, Option<LogicalType>)> = schema
+        .columns()
+        .iter()
+        .map(|column| (column.name(), column.physical_type(), column.logical_type()))
+        .collect();
+    assert_eq!(types[0], ("id", PhysicalType::INT64, None));
+    assert_eq!(types[1], ("lat", PhysicalType::DOUBLE, None));
+    assert_eq!(
+        types[5],
+        ("user", PhysicalType::BYTE_ARRAY, Some(LogicalType::String))
+    );
+    assert_eq!(types[7], ("visible", PhysicalType::BOOLEAN, None));
+    assert_eq!(types[8].0, "timestamp");
+    assert_eq!(types[8].1, PhysicalType::INT64);
+    assert!(matches!(
+        types[8].2,
+        Some(LogicalType::Timestamp {
+            is_adjusted_to_u_t_c: true,
+            ..
+        })
+    ));
+
+    let rows = fields(&nodes);
+    assert_eq!(rows[0][0], Field::Long(298884269));
+    assert_eq!(rows[0][5], Field::Str(String::from("SvenHRO")));
+    assert_eq!(rows[0][7], Field::Bool(true));
+    // 2008-09-21T21:37:45Z
+    assert_eq!(rows[0][8], Field::TimestampMillis(1_222_033_065_000));
+}
+
+#[test]
+fn parquet_null_values() {
+    let directory = convert("parquet-null", "sample/osm.xml", &["--format", "parquet"]);
+
+    let rows = fields(&open(&directory, "relation_members"));
+    assert_eq!(rows.len(), 4);
+    assert_eq!(
+        rows[2],
+        vec![
+            Field::Long(56688),
+            Field::Null,
+            Field::Long(4579143),
+            Field::Null,
+            Field::Str(String::new()),
+            Field::Int(2)
+        ]
+    );
+}
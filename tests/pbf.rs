@@ -0,0 +1,58 @@
+mod common;
+
+use common::pbf::PbfWriter;
+use common::*;
+
+#[test]
+fn pbf_output_matches_xml_output() {
+    let xml = convert("pbf-xml", "sample/osm.xml", &[]);
+    let pbf = convert("pbf-pbf", "sample/osm.pbf", &[]);
+
+    for table in TABLES.iter() {
+        assert_eq!(
+            read_table(&xml, table),
+            read_table(&pbf, table),
+            "{}",
+            table
+        );
+    }
+}
+
+#[test]
+fn elements_without_info_are_visible_in_both_formats() {
+    let xml = write_xml(
+        "pbf-no-info-xml-input",
+        " <node id=\"1\" lat=\"1.5\" lon=\"2.5\"><tag k=\"name\" v=\"a\"/></node>\n          <way id=\"2\"><nd ref=\"1\"/></way>\n          <relation id=\"3\"><member type=\"way\" ref=\"2\" role=\"outer\"/></relation>\n",
+    );
+    let mut writer = PbfWriter::without_info();
+    writer.node(1, 15_000_000, 25_000_000, "", &[("name", "a")]);
+    writer.way(2, "", &[1], &[]);
+    writer.relation(3, "", &[("way", 2, "outer")], &[]);
+    let pbf = output_directory("pbf-no-info-pbf-input").join("input.osm.pbf");
+    writer.write(&pbf);
+
+    let xml = convert("pbf-no-info-xml", xml.to_str().unwrap(), &[]);
+    let pbf = convert("pbf-no-info-pbf", pbf.to_str().unwrap(), &[]);
+
+    for table in TABLES.iter() {
+        assert_eq!(
+            read_table(&xml, table),
+            read_table(&pbf, table),
+            "{}",
+            table
+        );
+    }
+    let nodes = read_table(&xml, "nodes");
+    assert!(nodes.contains("(1,1.5,2.5,0,0,'',0,1,'')"), "{}", nodes);
+}
+
+#[test]
+fn pbf_format_is_detected_from_magic_bytes() {
+    let input = output_directory("pbf-magic").join("sample.data");
+    std::fs::copy("sample/osm.pbf", &input).unwrap();
+
+    let xml = convert("pbf-magic-xml", "sample/osm.xml", &[]);
+    let pbf = convert("pbf-magic-pbf", input.to_str().unwrap(), &[]);
+
+    assert_eq!(read_table(&xml, "nodes"), read_table(&pbf, "nodes"));
+}
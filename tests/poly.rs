@@ -0,0 +1,88 @@
+mod common;
+
+use common::sql::parse_rows;
+use common::*;
+
+/// A square with a square hole and a second small square. The first
+/// point of the hole is written in the scientific notation.
+const POLYGON: &str = "test area
+1
+   0.0E+00   0.0E+00
+   10 0
+   10 10
+   0 10
+   0 0
+END
+!hole
+   2.0E+00 2
+   4 2
+   4 4
+   2 4
+END
+island
+   20 20
+   22 20
+   22 22
+   20 22
+END
+END
+";
+
+fn input(name: &str) -> String {
+    let mut xml = String::new();
+    // Inside, in the hole, in the island, outside and at the east of the
+    // island but at its latitude.
+    for (id, lat, lon) in [
+        (1, "1", "1"),
+        (2, "3", "3"),
+        (3, "21", "21"),
+        (4, "15", "15"),
+        (5, "21", "15"),
+    ]
+    .iter()
+    {
+        xml += &node_xml(*id, lat, lon);
+    }
+    xml += &way_xml(10, &[2, 1], &[]);
+
+    String::from(write_xml(name, &xml).to_str().unwrap())
+}
+
+fn ids(directory: &std::path::Path, table: &str) -> Vec<String> {
+    parse_rows(&read_table(directory, table), "mysql")
+        .into_iter()
+        .map(|row| row[0].clone().unwrap())
+        .collect()
+}
+
+fn extract(name: &str, strategy: &str) -> std::path::PathBuf {
+    let poly = output_directory(&format!("{}-poly", name)).join("area.poly");
+    std::fs::write(&poly, POLYGON).unwrap();
+    convert(
+        name,
+        &input(&format!("{}-input", name)),
+        &["--polygon", poly.to_str().unwrap(), "--strategy", strategy],
+    )
+}
+
+#[test]
+fn nodes_in_holes_are_dropped() {
+    let directory = extract("poly-simple", "simple");
+    assert_eq!(ids(&directory, "nodes"), ["1", "3"]);
+    assert_eq!(ids(&directory, "ways"), ["10"]);
+    assert_eq!(
+        parse_rows(&read_table(&directory, "way_nodes"), "mysql"),
+        [[
+            Some(String::from("10")),
+            Some(String::from("1")),
+            Some(String::from("0"))
+        ]]
+    );
+}
+
+#[test]
+fn complete_ways_keep_nodes_in_holes() {
+    let directory = extract("poly-complete", "complete_ways");
+    assert_eq!(ids(&directory, "nodes"), ["1", "2", "3"]);
+    assert_eq!(ids(&directory, "way_nodes"), ["10", "10"]);
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::pbf::PbfWriter;
+use common::sql::parse_rows;
+use common::*;
+
+fn row(values: &[i64]) -> Vec<Option<String>> {
+    values.iter().map(|value| Some(value.to_string())).collect()
+}
+
+#[test]
+fn way_nodes_keep_order_and_repeated_nodes() {
+    let closed_way = way_xml(10, &[3, 1, 2, 3], &[]) + &way_xml(11, &[2, 1], &[]);
+    let xml_input = write_xml("sequence-xml-input", &closed_way);
+
+    let mut pbf = PbfWriter::new();
+    pbf.way(10, "u", &[3, 1, 2, 3], &[]);
+    pbf.way(11, "u", &[2, 1], &[]);
+    let pbf_input = output_directory("sequence-pbf-input").join("input.osm.pbf");
+    pbf.write(&pbf_input);
+
+    for input in [xml_input, pbf_input].iter() {
+        for dialect in ["mysql", "postgres", "sqlite"].iter() {
+            let directory = convert("sequence", input.to_str().unwrap(), &["--dialect", dialect]);
+            let sql = read_table(&directory, "way_nodes");
+            assert_eq!(
+                parse_rows(&sql, dialect),
+                vec![
+                    row(&[10, 3, 0]),
+                    row(&[10, 1, 1]),
+                    row(&[10, 2, 2]),
+                    row(&[10, 3, 3]),
+                    row(&[11, 2, 0]),
+                    row(&[11, 1, 1]),
+                ]
+            );
+            assert!(sql.contains("PRIMARY KEY(way_id,sequence_id)"));
+            assert!(sql.contains("CREATE INDEX way_nodes_node_id_idx ON way_nodes (node_id);"));
+        }
+    }
+}
+
+#[test]
+fn relation_members_keep_order_and_duplicates() {
+    let route = relation_xml(
+        100,
+        &[
+            ("way", 10, "forward"),
+            ("node", 1, "stop"),
+            ("way", 10, "backward"),
+            ("relation", 101, ""),
+        ],
+        &[],
+    ) + &relation_xml(101, &[("way", 10, "")], &[]);
+    let xml_input = write_xml("sequence-members-xml-input", &route);
+
+    let mut pbf = PbfWriter::new();
+    pbf.relation(
+        100,
+        "u",
+        &[
+            ("way", 10, "forward"),
+            ("node", 1, "stop"),
+            ("way", 10, "backward"),
+            ("relation", 101, ""),
+        ],
+        &[],
+    );
+    pbf.relation(101, "u", &[("way", 10, "")], &[]);
+    let pbf_input = output_directory("sequence-members-pbf-input").join("input.osm.pbf");
+    pbf.write(&pbf_input);
+
+    let member = |relation: &str, node: &str, way: &str, sub: &str, role: &str, sequence: &str| {
+        [relation, node, way, sub, role, sequence]
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                if value.is_empty() && i != 4 {
+                    None
+                } else {
+                    Some(value.to_string())
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    for input in [xml_input, pbf_input].iter() {
+        for dialect in ["mysql", "postgres", "sqlite"].iter() {
+            let directory = convert(
+                "sequence-members",
+                input.to_str().unwrap(),
+                &["--dialect", dialect],
+            );
+            let sql = read_table(&directory, "relation_members");
+            assert_eq!(
+                parse_rows(&sql, dialect),
+                vec![
+                    member("100", "", "10", "", "forward", "0"),
+                    member("100", "1", "", "", "stop", "1"),
+                    member("100", "", "10", "", "backward", "2"),
+                    member("100", "", "", "101", "", "3"),
+                    member("101", "", "10", "", "", "0"),
+                ]
+            );
+            assert!(sql.contains("PRIMARY KEY(relation_id,sequence_id)"));
+        }
+    }
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::*;
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn stdin_to_stdout_writes_tables_in_import_order() {
+    let files = convert("stdio-files", "sample/osm.xml", &[]);
+    let expected: String = TABLES
+        .iter()
+        .map(|table| read_table(&files, table))
+        .collect();
+
+    let stdout = run_with_stdin(
+        &["-i", "-", "-d", "-"],
+        &fs::read("sample/osm.xml").unwrap(),
+    );
+
+    assert_eq!(String::from_utf8(stdout).unwrap(), expected);
+}
+
+#[test]
+fn compressed_stdin() {
+    let files = convert("stdio-compressed-files", "sample/osm.xml", &[]);
+    let directory = output_directory("stdio-compressed");
+
+    run_with_stdin(
+        &["-i", "-", "-d", directory.to_str().unwrap()],
+        &fs::read("sample/osm.xml.bz2").unwrap(),
+    );
+
+    for table in TABLES.iter() {
+        assert_eq!(read_table(&files, table), read_table(&directory, table));
+    }
+}
+
+#[test]
+fn spooled_tables_are_removed_when_the_conversion_fails() {
+    let xml = fs::read_to_string("sample/osm.xml").unwrap();
+    let input = output_directory("stdio-failed-input").join("invalid.osm");
+    fs::write(&input, xml.replace("</osm>", "<node id=\"x\"/></osm>")).unwrap();
+    let temp = output_directory("stdio-failed-temp");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_osm-to-sql"))
+        .args(["-i", input.to_str().unwrap(), "-d", "-"])
+        .env("TMPDIR", &temp)
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(fs::read_dir(&temp).unwrap().count(), 0);
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use common::sql::parse_rows;
+use common::*;
+use rusqlite::Connection;
+
+fn elements() -> String {
+    tagged_node_xml(
+        1,
+        "1",
+        "2",
+        &[
+            ("name", "Cafe"),
+            ("layer", "-1"),
+            ("addr:street", "Main Street"),
+            ("amenity", "cafe"),
+        ],
+    ) + &way_xml(2, &[1], &[("layer", "1;2"), ("highway", "path")])
+}
+
+const COLUMNS: &str = "name,layer=integer,addr:street";
+
+#[test]
+fn tags_are_written_to_their_columns() {
+    let input = write_xml("tag-columns-input", &elements());
+    let directory = convert(
+        "tag-columns",
+        input.to_str().unwrap(),
+        &["--columns", COLUMNS],
+    );
+
+    let nodes = read_table(&directory, "nodes");
+    assert!(nodes.contains("`name` VARCHAR(256),`layer` INTEGER,`addr:street` VARCHAR(256)"));
+    let nodes = parse_rows(&nodes, "mysql");
+    assert_eq!(
+        nodes[0][9..],
+        [
+            Some(String::from("Cafe")),
+            Some(String::from("-1")),
+            Some(String::from("Main Street"))
+        ]
+    );
+
+    // Values that are not integers are left in the tags.
+    let ways = parse_rows(&read_table(&directory, "ways"), "mysql");
+    assert_eq!(ways[0][7..], [None, None, None]);
+
+    let tags: Vec<Option<String>> = parse_rows(&read_table(&directory, "tags"), "mysql")
+        .into_iter()
+        .map(|row| row[1].clone())
+        .collect();
+    assert_eq!(
+        tags,
+        [
+            Some(String::from("amenity")),
+            Some(String::from("layer")),
+            Some(String::from("highway"))
+        ]
+    );
+    let values: Vec<Option<String>> = parse_rows(&read_table(&directory, "ref_tags"), "mysql")
+        .into_iter()
+        .map(|row| row[4].clone())
+        .collect();
+    assert_eq!(
+        values,
+        [
+            Some(String::from("cafe")),
+            Some(String::from("1;2")),
+            Some(String::from("path"))
+        ]
+    );
+}
+
+#[test]
+fn tag_columns_are_written_to_the_database() {
+    let input = write_xml("tag-columns-database-input", &elements());
+    let directory = output_directory("tag-columns-database");
+    let database = directory.join("osm.db");
+    run(&[
+        "-i",
+        input.to_str().unwrap(),
+        "--database",
+        database.to_str().unwrap(),
+        "--columns",
+        COLUMNS,
+        "--tags",
+        "json",
+    ]);
+
+    let connection = Connection::open(&database).unwrap();
+    let (name, layer, street, tags): (String, i32, String, String) = connection
+        .query_row(
+            "SELECT name, layer, \"addr:street\", tags FROM nodes",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .unwrap();
+    assert_eq!(
+        (name.as_str(), layer, street.as_str(), tags.as_str()),
+        ("Cafe", -1, "Main Street", "{\"amenity\":\"cafe\"}")
+    );
+}
+
+#[test]
+fn keyword_tag_columns_are_quoted() {
+    let input = write_xml("tag-columns-keywords-input", &elements());
+    for (dialect, definitions) in [
+        ("mysql", "`natural` VARCHAR(256),`order` VARCHAR(256)"),
+        ("postgres", "\"natural\" TEXT,\"order\" TEXT"),
+    ] {
+        let directory = convert(
+            &format!("tag-columns-keywords-{}", dialect),
+            input.to_str().unwrap(),
+            &["--columns", "natural,order", "--dialect", dialect],
+        );
+        assert!(read_table(&directory, "nodes").contains(definitions));
+    }
+
+    let directory = convert(
+        "tag-columns-keywords-sqlite",
+        input.to_str().unwrap(),
+        &["--columns", "natural,order", "--dialect", "sqlite"],
+    );
+    let connection = Connection::open_in_memory().unwrap();
+    connection
+        .execute_batch(&read_table(&directory, "nodes"))
+        .unwrap();
+    let columns = query_column(&connection, "SELECT name FROM pragma_table_info('nodes')");
+    assert_eq!(columns[columns.len() - 2..], ["natural", "order"]);
+}
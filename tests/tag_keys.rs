@@ -0,0 +1,74 @@
+mod common;
+
+use common::sql::parse_rows;
+use common::*;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Writes nodes with distinct tag keys and one shared tag key.
+fn distinct_keys_input(keys: usize) -> String {
+    let mut elements = String::new();
+    for i in 0..keys {
+        let key = format!("key:{}", i);
+        elements += &tagged_node_xml(i as i64 + 1, "1", "2", &[(&key, "v"), ("shared", "v")]);
+    }
+    let path = write_xml(&format!("tag-keys-{}-input", keys), &elements);
+    String::from(path.to_str().unwrap())
+}
+
+fn convert_timed(keys: usize) -> (std::path::PathBuf, Duration) {
+    let input = distinct_keys_input(keys);
+    let start = Instant::now();
+    let directory = convert(&format!("tag-keys-{}", keys), &input, &[]);
+    (directory, start.elapsed())
+}
+
+#[test]
+fn distinct_tag_keys_have_unique_ids() {
+    // More keys than a 16 bit id can count.
+    let keys = 40_000;
+    let (directory, _) = convert_timed(keys);
+
+    let tags = parse_rows(&read_table(&directory, "tags"), "mysql");
+    assert_eq!(tags.len(), keys + 1);
+    let names: HashMap<i64, String> = tags
+        .into_iter()
+        .map(|row| {
+            (
+                row[0].clone().unwrap().parse().unwrap(),
+                row[1].clone().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(names.len(), keys + 1, "tag ids are not unique");
+    assert!(names.keys().all(|id| *id >= 0 && *id <= keys as i64));
+
+    // Every node references its own key and the shared key.
+    let ref_tags = parse_rows(&read_table(&directory, "ref_tags"), "mysql");
+    assert_eq!(ref_tags.len(), keys * 2);
+    for (i, pair) in ref_tags.chunks(2).enumerate() {
+        let name = |row: &Vec<Option<String>>| &names[&row[0].clone().unwrap().parse().unwrap()];
+        assert_eq!(name(&pair[0]), &format!("key:{}", i));
+        assert_eq!(name(&pair[1]), "shared");
+    }
+}
+
+/// The key lookup should not depend on the number of known keys. A linear
+/// scan takes sixteen times longer for four times more keys. Timings depend
+/// on the machine, so run it with `cargo test -- --ignored`.
+#[test]
+#[ignore]
+fn tag_key_lookup_scales_with_the_number_of_keys() {
+    let keys = 100_000;
+    let (_, large) = convert_timed(keys);
+    let (_, small) = convert_timed(keys / 4);
+
+    assert!(
+        large.as_secs_f64() / small.as_secs_f64() < 8.0,
+        "{} keys took {:?}, {} keys took {:?}",
+        keys / 4,
+        small,
+        keys,
+        large
+    );
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::pbf::PbfWriter;
+use common::sql::parse_rows;
+use common::*;
+use rusqlite::Connection;
+
+const TAGS: [(&str, &str); 3] = [
+    ("name", "quote \" back \\ new\nline nul \0 emoji \u{1F600}"),
+    ("control", "\u{1}\t\r"),
+    ("", ""),
+];
+
+/// Writes a node, a way and a relation with the same tags.
+fn tagged_input(name: &str) -> String {
+    let mut pbf = PbfWriter::new();
+    pbf.node(1, 10, 20, "u", &TAGS);
+    pbf.way(2, "u", &[1], &TAGS);
+    pbf.relation(3, "u", &[("way", 2, "outer")], &TAGS);
+
+    let path = output_directory(name).join("input.osm.pbf");
+    pbf.write(&path);
+    String::from(path.to_str().unwrap())
+}
+
+#[test]
+fn tags_are_written_to_json_columns() {
+    let directory = convert("tag-storage-json", "sample/osm.xml", &["--tags", "json"]);
+    assert!(!directory.join("tags.sql").exists());
+    assert!(!directory.join("ref_tags.sql").exists());
+
+    let ways = read_table(&directory, "ways");
+    assert!(ways.contains("tags JSON"));
+    let rows = parse_rows(&ways, "mysql");
+    assert_eq!(
+        rows[0].last().unwrap().as_deref(),
+        Some("{\"highway\":\"unclassified\",\"name\":\"Pastower Straße\"}")
+    );
+
+    // Elements without tags have an empty object.
+    let nodes = parse_rows(&read_table(&directory, "nodes"), "mysql");
+    assert!(nodes
+        .iter()
+        .any(|row| row.last().unwrap().as_deref() == Some("{}")));
+}
+
+#[test]
+fn json_tags_are_readable_by_sqlite() {
+    let input = tagged_input("tag-storage-sqlite-input");
+    let directory = output_directory("tag-storage-sqlite");
+    let database = directory.join("osm.db");
+    run(&[
+        "-i",
+        &input,
+        "--database",
+        database.to_str().unwrap(),
+        "--tags",
+        "json",
+    ]);
+
+    let connection = Connection::open(&database).unwrap();
+    for table in ["nodes", "ways", "relations"].iter() {
+        for (k, v) in TAGS.iter() {
+            let value: String = connection
+                .query_row(
+                    &format!("SELECT tags ->> ? FROM {}", table),
+                    [format!("$.\"{}\"", k.replace('"', "\\\""))],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(value, *v, "{} {:?}", table, k);
+        }
+    }
+}
+
+#[test]
+fn hstore_tags_are_written_for_postgres() {
+    let input = tagged_input("tag-storage-hstore-input");
+    let directory = convert(
+        "tag-storage-hstore",
+        &input,
+        &["--dialect", "postgres", "--tags", "hstore"],
+    );
+
+    let nodes = read_table(&directory, "nodes");
+    assert!(nodes.starts_with("CREATE EXTENSION IF NOT EXISTS hstore;\n"));
+    assert!(nodes.contains("tags HSTORE"));
+    let rows = parse_rows(&nodes, "postgres");
+    assert_eq!(
+        rows[0].last().unwrap().as_deref(),
+        Some("\"name\"=>\"quote \\\" back \\\\ new\nline nul \u{FFFD} emoji \u{1F600}\", \"control\"=>\"\u{1}\t\r\", \"\"=>\"\"")
+    );
+}
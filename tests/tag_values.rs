@@ -0,0 +1,80 @@
+mod common;
+
+use common::sql::parse_rows;
+use common::*;
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+
+/// Tag values of the ref_tags rows in the order of the rows.
+fn values(directory: &std::path::Path) -> Vec<String> {
+    parse_rows(&read_table(directory, "ref_tags"), "mysql")
+        .into_iter()
+        .map(|row| row[4].clone().unwrap())
+        .collect()
+}
+
+#[test]
+fn tag_values_are_normalized() {
+    let plain = convert("tag-values-plain", "sample/osm.xml", &[]);
+    assert!(!plain.join("tag_values.sql").exists());
+
+    let directory = convert("tag-values", "sample/osm.xml", &["--normalize-values"]);
+    let tag_values: HashMap<String, String> =
+        parse_rows(&read_table(&directory, "tag_values"), "mysql")
+            .into_iter()
+            .map(|row| (row[0].clone().unwrap(), row[1].clone().unwrap()))
+            .collect();
+
+    // Every distinct value is written once.
+    let distinct: HashSet<String> = values(&plain).into_iter().collect();
+    assert_eq!(tag_values.len(), distinct.len());
+
+    let resolved: Vec<String> = values(&directory)
+        .iter()
+        .map(|value_id| tag_values[value_id].clone())
+        .collect();
+    assert_eq!(resolved, values(&plain));
+}
+
+#[test]
+fn tag_values_are_written_to_the_database() {
+    let directory = output_directory("tag-values-database");
+    let database = directory.join("osm.db");
+    let database = database.to_str().unwrap();
+    run(&[
+        "-i",
+        "sample/osm.xml",
+        "--database",
+        database,
+        "--normalize-values",
+    ]);
+
+    let connection = Connection::open(database).unwrap();
+    let missing: i64 = connection
+        .query_row(
+            "SELECT count(*) FROM ref_tags LEFT JOIN tag_values ON tag_values.id = value_id WHERE tag_values.id IS NULL",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(missing, 0);
+
+    let value: String = connection
+        .query_row(
+            "SELECT tag_values.value FROM ref_tags JOIN tags ON tags.id = tag_id JOIN tag_values ON tag_values.id = value_id WHERE tags.name = 'highway'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(value, "unclassified");
+}
+
+#[test]
+fn tag_values_are_written_to_the_standard_output() {
+    let output = run(&["-i", "sample/osm.xml", "-d", "-", "--normalize-values"]);
+    let output = String::from_utf8(output.stdout).unwrap();
+
+    let tag_values = output.find("CREATE TABLE tag_values ").unwrap();
+    let ref_tags = output.find("CREATE TABLE ref_tags ").unwrap();
+    assert!(tag_values < ref_tags);
+}
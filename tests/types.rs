@@ -0,0 +1,72 @@
+mod common;
+
+use common::sql::parse_rows;
+use common::*;
+use rusqlite::Connection;
+
+#[test]
+fn only_given_types_are_written() {
+    let directory = convert("types-files", "sample/osm.xml", &["--types", "nodes,ways"]);
+
+    for table in ["nodes", "ways", "way_nodes", "tags", "ref_tags"] {
+        assert!(directory.join(format!("{}.sql", table)).exists());
+    }
+    for table in ["relations", "relation_members"] {
+        assert!(!directory.join(format!("{}.sql", table)).exists());
+    }
+
+    let ref_tags = read_table(&directory, "ref_tags");
+    assert!(!ref_tags.contains("REFERENCES relations"));
+    let rows = parse_rows(&ref_tags, "mysql");
+    assert!(!rows.is_empty());
+    // Tags are only written for nodes and ways.
+    assert!(rows.iter().all(|row| row[2].is_none()));
+}
+
+#[test]
+fn skipped_tables_are_not_referenced() {
+    let directory = output_directory("types-database");
+    let database = directory.join("osm.db");
+    run(&[
+        "-i",
+        "sample/osm.xml",
+        "--database",
+        database.to_str().unwrap(),
+        "--types",
+        "ways",
+    ]);
+
+    let connection = Connection::open(&database).unwrap();
+    assert_eq!(
+        query_column(
+            &connection,
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name"
+        ),
+        vec!["ref_tags", "tags", "way_nodes", "ways"]
+    );
+    assert!(query_column(&connection, "PRAGMA foreign_key_check").is_empty());
+    assert_eq!(
+        query_column(
+            &connection,
+            "SELECT \"table\" FROM pragma_foreign_key_list('way_nodes')"
+        ),
+        vec!["ways"]
+    );
+}
+
+#[test]
+fn metadata_columns_are_left_out() {
+    let directory = convert(
+        "types-no-metadata",
+        "sample/osm.xml",
+        &["--no-metadata", "--tags", "json"],
+    );
+
+    let nodes = read_table(&directory, "nodes");
+    assert!(nodes.contains("INSERT IGNORE INTO nodes (id,lat,lng,tags) VALUES"));
+    for table in ["ways", "relations"] {
+        let sql = read_table(&directory, table);
+        assert!(sql.contains(&format!("INSERT IGNORE INTO {} (id,tags) VALUES", table)));
+        assert!(!sql.contains("changeset"));
+    }
+}